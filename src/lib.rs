@@ -1,258 +1,6339 @@
 pub mod oop {
-    use std::cell::RefCell;
+    use crate::rust_way;
+    use std::borrow::Cow;
+    use std::cell::{Cell, RefCell};
+    use std::cmp::{Ordering, Reverse};
+    use std::collections::{HashMap, HashSet};
+    use std::error::Error;
+    use std::fmt;
+    use std::io::{self, Read};
+    use std::rc::Rc;
+    use std::time::{Duration, SystemTime};
+
+    pub trait Clock {
+        fn now(&self) -> SystemTime;
+    }
+
+    pub struct SystemClock;
+
+    impl Clock for SystemClock {
+        fn now(&self) -> SystemTime {
+            SystemTime::now()
+        }
+    }
+
+    pub struct FixedClock {
+        now: Cell<SystemTime>,
+    }
+
+    impl FixedClock {
+        pub fn new(now: SystemTime) -> FixedClock {
+            FixedClock {
+                now: Cell::new(now),
+            }
+        }
+        pub fn set(&self, now: SystemTime) {
+            self.now.set(now);
+        }
+        pub fn advance(&self, by: Duration) {
+            self.now.set(self.now.get() + by);
+        }
+    }
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            self.now.get()
+        }
+    }
+
+    type ContentPredicate = dyn Fn(&str) -> bool;
+    type TextListener = dyn FnMut(&str);
+    type ContentTransform = dyn Fn(&str) -> String;
 
     pub struct Post {
         state: Option<Box<dyn State>>,
         content: String,
+        clock: Rc<dyn Clock>,
+        created_at: SystemTime,
+        state_since: Option<SystemTime>,
+        post_type: PostType,
+        required_approvals: u8,
+        required_rejections: u8,
+        required_roles: Vec<String>,
+        reviewer_allowlist: Vec<String>,
+        assigned_reviewers: Vec<(String, SystemTime)>,
+        approval_weights: Vec<(String, u8)>,
+        review_quorum: Option<f64>,
+        embargo: Option<u8>,
+        max_review_words: Option<usize>,
+        max_avg_sentence_words: Option<usize>,
+        required_sections: Vec<String>,
+        comments: Vec<String>,
+        was_edited: bool,
+        redactions: Vec<String>,
+        profanity_filter: Option<(Vec<String>, char)>,
+        style_replacements: Vec<(String, String)>,
+        content_transforms: Vec<Rc<ContentTransform>>,
+        link_validator: Option<Box<ContentPredicate>>,
+        id: Option<u64>,
+        title: String,
+        slug: Option<String>,
+        language: String,
+        author: String,
+        base_url: Option<String>,
+        source_id: Option<u64>,
+        tags: Vec<String>,
+        dictionary: Option<HashSet<String>>,
+        sticky: bool,
+        auto_tag: bool,
+        normalize_newlines: bool,
+        strip_bom: bool,
+        title_case_headings: bool,
+        strip_emoji: bool,
+        smart_quotes: bool,
+        normalize_headings: bool,
+        dedup_paragraphs: bool,
+        recording: Option<Vec<Command>>,
+        publish_guard: Option<Box<ContentPredicate>>,
+        publish_vetoes: u32,
+        transition_cooldown: Option<Duration>,
+        draft_ttl: Option<Duration>,
+        #[cfg(feature = "webhook")]
+        webhook: Option<String>,
+        #[cfg(feature = "webhook")]
+        webhook_sender: Rc<WebhookSender>,
+        history: Vec<Transition>,
+        revisions: Vec<String>,
+        trim_on_publish: bool,
+        max_revisions: Option<u32>,
+        role_subscribers: Vec<(String, Rc<dyn Subscriber>)>,
+        subscribers: Vec<Rc<dyn Subscriber>>,
+        text_listeners: Vec<Box<TextListener>>,
     }
 
     impl Post {
         pub fn new() -> Post {
+            let clock: Rc<dyn Clock> = Rc::new(SystemClock);
             Post {
                 state: Some(Box::new(Draft {})),
                 content: String::new(),
+                created_at: clock.now(),
+                state_since: None,
+                clock,
+                post_type: PostType::Article,
+                required_approvals: PostType::Article.required_approvals(),
+                required_rejections: 1,
+                required_roles: Vec::new(),
+                reviewer_allowlist: Vec::new(),
+                assigned_reviewers: Vec::new(),
+                approval_weights: Vec::new(),
+                review_quorum: None,
+                embargo: None,
+                max_review_words: None,
+                max_avg_sentence_words: None,
+                required_sections: Vec::new(),
+                comments: Vec::new(),
+                was_edited: false,
+                redactions: Vec::new(),
+                profanity_filter: None,
+                style_replacements: Vec::new(),
+                content_transforms: Vec::new(),
+                link_validator: None,
+                id: None,
+                title: String::new(),
+                slug: None,
+                language: String::new(),
+                author: String::new(),
+                base_url: None,
+                source_id: None,
+                tags: Vec::new(),
+                dictionary: None,
+                sticky: false,
+                auto_tag: false,
+                normalize_newlines: false,
+                strip_bom: true,
+                title_case_headings: false,
+                strip_emoji: false,
+                smart_quotes: false,
+                normalize_headings: false,
+                dedup_paragraphs: false,
+                recording: None,
+                publish_guard: None,
+                publish_vetoes: 0,
+                transition_cooldown: None,
+                draft_ttl: None,
+                #[cfg(feature = "webhook")]
+                webhook: None,
+                #[cfg(feature = "webhook")]
+                webhook_sender: Rc::new(http_post),
+                history: Vec::new(),
+                revisions: Vec::new(),
+                trim_on_publish: false,
+                max_revisions: None,
+                role_subscribers: Vec::new(),
+                subscribers: Vec::new(),
+                text_listeners: Vec::new(),
             }
         }
-        pub fn add_text(&mut self, text: &str) {
-            self.content = self.state.as_ref().unwrap().add_text(&self.content, text);
+        pub fn with_clock(mut self, clock: Rc<dyn Clock>) -> Post {
+            self.created_at = clock.now();
+            self.clock = clock;
+            self
         }
-        pub fn content(&self) -> &str {
-            self.state.as_ref().unwrap().content(self)
+        pub fn created_at(&self) -> SystemTime {
+            self.created_at
         }
-        pub fn request_review(&mut self) {
-            if let Some(s) = self.state.take() {
-                self.state = Some(s.request_review())
+        /// When the post entered its current state, or `None` while it is still
+        /// in the draft it was created in.
+        pub fn state_since(&self) -> Option<SystemTime> {
+            self.state_since
+        }
+        pub fn is_review_overdue(&self, max: Duration) -> bool {
+            match self.state_since {
+                Some(since) if self.is_pending() => self
+                    .clock
+                    .now()
+                    .duration_since(since)
+                    .is_ok_and(|pending_for| pending_for > max),
+                _ => false,
             }
         }
-        pub fn approve(&mut self) {
-            if let Some(s) = self.state.take() {
-                self.state = Some(s.approve())
+        /// Sends the feed item as JSON to `url` when the post is published.
+        /// Delivery is fire-and-forget: failures don't affect the post.
+        #[cfg(feature = "webhook")]
+        pub fn with_webhook(mut self, url: String) -> Post {
+            self.webhook = Some(url);
+            self
+        }
+        /// Replaces the built-in HTTP client used by `with_webhook`.
+        #[cfg(feature = "webhook")]
+        pub fn with_webhook_sender(
+            mut self,
+            f: impl Fn(&str, &str) -> io::Result<()> + 'static,
+        ) -> Post {
+            self.webhook_sender = Rc::new(f);
+            self
+        }
+        pub fn with_draft_ttl(mut self, d: Duration) -> Post {
+            self.draft_ttl = Some(d);
+            self
+        }
+        /// True for drafts created longer than the TTL ago; pending and
+        /// published posts never expire.
+        pub fn is_expired(&self) -> bool {
+            match self.draft_ttl {
+                Some(ttl) if self.state() == PostState::Draft => self
+                    .clock
+                    .now()
+                    .duration_since(self.created_at)
+                    .is_ok_and(|age| age > ttl),
+                _ => false,
             }
         }
-        pub fn reject(&mut self) {
-            if let Some(s) = self.state.take() {
-                self.state = Some(s.reject())
+        pub fn with_type(mut self, t: PostType) -> Post {
+            self.set_type(t);
+            self
+        }
+        /// Changes the type, and the approvals it requires, while in draft.
+        pub fn set_type(&mut self, t: PostType) {
+            if self.state() == PostState::Draft {
+                self.post_type = t;
+                self.required_approvals = t.required_approvals();
             }
         }
-    }
-
-    trait State {
-        fn request_review(self: Box<Self>) -> Box<dyn State>;
-        fn approve(self: Box<Self>) -> Box<dyn State>;
-        fn reject(self: Box<Self>) -> Box<dyn State>;
-        fn content<'a>(&self, _post: &'a Post) -> &'a str {
-            ""
+        pub fn post_type(&self) -> PostType {
+            self.post_type
         }
-        fn add_text(&self, current_content: &str, _text_to_append: &str) -> String {
-            current_content.to_string()
+        pub fn with_required_rejections(mut self, n: u8) -> Post {
+            self.required_rejections = n;
+            self
         }
-    }
-
-    struct Draft {}
-
-    impl State for Draft {
-        fn request_review(self: Box<Self>) -> Box<dyn State> {
-            Box::new(PendingReview {
-                approvals: RefCell::new(0),
+        /// Restricts `approve_by` and `reject_by` to the listed reviewers.
+        pub fn with_reviewer_allowlist(mut self, reviewers: &[&str]) -> Post {
+            self.reviewer_allowlist = reviewers.iter().map(|name| name.to_string()).collect();
+            self
+        }
+        /// Lets some reviewers' approvals count more than one towards
+        /// `required_approvals`. Unlisted and anonymous approvals weigh one.
+        pub fn with_approval_weights(mut self, weights: &[(&str, u8)]) -> Post {
+            self.approval_weights = weights
+                .iter()
+                .map(|(name, weight)| (name.to_string(), *weight))
+                .collect();
+            self
+        }
+        pub fn current_approval_weight(&self) -> u32 {
+            self.approval_weight(self.state.as_deref().unwrap())
+        }
+        fn approval_weight(&self, state: &dyn State) -> u32 {
+            let approvers = state.approvers();
+            let anonymous = (state.approvals() as usize).saturating_sub(approvers.len()) as u32;
+            let named: u32 = approvers
+                .iter()
+                .map(|approver| {
+                    self.approval_weights
+                        .iter()
+                        .find(|(name, _)| name == approver)
+                        .map_or(1, |(_, weight)| *weight as u32)
+                })
+                .sum();
+            anonymous + named
+        }
+        /// Replaces the fixed approval count with a share of the assigned
+        /// reviewers, e.g. `0.5` for half of them rounded up. The fraction is
+        /// clamped to `0.0..=1.0`, NaN counts as `1.0`, and at least one assigned
+        /// reviewer must always approve. Nothing publishes while nobody is
+        /// assigned.
+        pub fn with_review_quorum(mut self, fraction: f64) -> Post {
+            let fraction = if fraction.is_nan() {
+                1.0
+            } else {
+                fraction.clamp(0.0, 1.0)
+            };
+            self.review_quorum = Some(fraction);
+            self
+        }
+        /// The assigned-reviewer approvals needed to publish, or `None` without
+        /// a quorum rule or while no reviewers are assigned.
+        pub fn quorum_target(&self) -> Option<u32> {
+            let assigned = self.assigned_reviewers.len();
+            if assigned == 0 {
+                return None;
+            }
+            self.review_quorum
+                .map(|fraction| ((fraction * assigned as f64).ceil() as u32).max(1))
+        }
+        /// How far a pending post is towards publication, from 0.0 to 1.0,
+        /// measured against the quorum target or the weighted approval count.
+        pub fn approval_progress(&self) -> f64 {
+            match self.state() {
+                PostState::Draft => 0.0,
+                PostState::Published => 1.0,
+                PostState::PendingReview => {
+                    match self.approval_score(self.state.as_deref().unwrap()) {
+                        Some((current, threshold)) if threshold > 0 => {
+                            (current as f64 / threshold as f64).min(1.0)
+                        }
+                        Some(_) => 1.0,
+                        None => 0.0,
+                    }
+                }
+            }
+        }
+        /// The approvals counted so far and the number needed to publish, or
+        /// `None` while a quorum rule has nobody assigned to reach it.
+        fn approval_score(&self, state: &dyn State) -> Option<(u32, u32)> {
+            if self.review_quorum.is_none() {
+                return Some((self.approval_weight(state), self.required_approvals as u32));
+            }
+            let target = self.quorum_target()?;
+            let approved = self
+                .assigned_reviewers
+                .iter()
+                .filter(|(name, _)| state.has_approved(name))
+                .count() as u32;
+            Some((approved, target))
+        }
+        fn approvals_met(&self, state: &dyn State) -> bool {
+            self.approval_score(state)
+                .is_some_and(|(current, threshold)| current >= threshold)
+        }
+        fn may_review(&self, reviewer: &str) -> bool {
+            self.reviewer_allowlist.is_empty()
+                || self.reviewer_allowlist.iter().any(|name| name == reviewer)
+        }
+        pub fn with_required_roles(mut self, roles: &[&str]) -> Post {
+            self.required_roles = roles.iter().map(|role| role.to_string()).collect();
+            self
+        }
+        pub fn with_max_review_words(mut self, n: usize) -> Post {
+            self.max_review_words = Some(n);
+            self
+        }
+        pub fn exceeds_review_word_limit(&self) -> bool {
+            self.max_review_words
+                .is_some_and(|max| self.content.split_whitespace().count() > max)
+        }
+        pub fn with_id(mut self, id: u64) -> Post {
+            self.id = Some(id);
+            self
+        }
+        pub fn id(&self) -> Option<u64> {
+            self.id
+        }
+        pub fn with_title(mut self, title: &str) -> Post {
+            self.set_title(title);
+            self
+        }
+        pub fn set_title(&mut self, title: &str) {
+            self.title = title.to_string();
+        }
+        pub fn title(&self) -> &str {
+            &self.title
+        }
+        pub fn set_slug(&mut self, slug: &str) {
+            self.slug = Some(slug.to_string());
+        }
+        pub fn slug(&self) -> String {
+            match &self.slug {
+                Some(slug) => slug.clone(),
+                None => slugify(&self.title),
+            }
+        }
+        pub fn share_url(&self, base: &str) -> String {
+            format!("{}/{}", base.trim_end_matches('/'), self.slug())
+        }
+        pub fn with_author(mut self, author: &str) -> Post {
+            self.author = author.to_string();
+            self
+        }
+        pub fn author(&self) -> &str {
+            &self.author
+        }
+        /// The fields an RSS or Atom entry needs; the link is relative to the
+        /// site root.
+        pub fn to_feed_item(&self) -> Option<FeedItem> {
+            if self.state() != PostState::Published {
+                return None;
+            }
+            Some(FeedItem {
+                title: self.title.clone(),
+                link: self.share_url(""),
+                content: self.content().into_owned(),
+                published_at: self.published_at()?,
+                author: self.author.clone(),
+                sticky: self.sticky,
             })
         }
-        fn approve(self: Box<Self>) -> Box<dyn State> {
+        fn published_at(&self) -> Option<SystemTime> {
+            (self.state() == PostState::Published)
+                .then(|| self.state_since.unwrap_or(self.created_at))
+        }
+        /// Pins the post to the top of feeds; allowed in any state.
+        pub fn set_sticky(&mut self, sticky: bool) {
+            self.sticky = sticky;
+        }
+        pub fn is_sticky(&self) -> bool {
+            self.sticky
+        }
+        pub fn with_language(mut self, lang: &str) -> Post {
+            self.set_language(lang);
             self
         }
-        fn reject(self: Box<Self>) -> Box<dyn State> {
+        pub fn set_language(&mut self, lang: &str) {
+            self.language = lang.to_string();
+        }
+        pub fn language(&self) -> &str {
+            &self.language
+        }
+        pub fn with_redactions(mut self, words: &[&str]) -> Post {
+            self.redactions = words.iter().map(|word| word.to_string()).collect();
             self
         }
-        fn add_text(&self, current_content: &str, text_to_append: &str) -> String {
-            format!("{}{}", current_content, text_to_append)
+        /// Masks each listed word in `content()` with `mask`, one per character.
+        /// A lightweight filter for family-friendly output, not moderation.
+        pub fn with_profanity_filter(mut self, words: &[&str], mask: char) -> Post {
+            let words = words.iter().map(|word| word.to_string()).collect();
+            self.profanity_filter = Some((words, mask));
+            self
         }
-    }
-
-    struct PendingReview {
-        approvals: RefCell<u8>,
-    }
-
-    impl State for PendingReview {
-        fn request_review(self: Box<Self>) -> Box<dyn State> {
+        /// House-style rewrites for `content()`, applied one after another in
+        /// the given order.
+        pub fn with_style_replacements(mut self, map: &[(&str, &str)]) -> Post {
+            self.style_replacements = map
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect();
             self
         }
-        fn approve(self: Box<Self>) -> Box<dyn State> {
-            *self.approvals.borrow_mut() += 1;
-            if *self.approvals.borrow() > 1 {
-                Box::new(Published {})
+        /// Appends `f` to the transforms `content()` applies in registration
+        /// order, after the built-in toggles and before any embargo scrambling.
+        pub fn add_content_transform(&mut self, f: impl Fn(&str) -> String + 'static) {
+            self.content_transforms.push(Rc::new(f));
+        }
+        pub fn with_normalize_newlines(mut self, enabled: bool) -> Post {
+            self.normalize_newlines = enabled;
+            self
+        }
+        pub fn with_publish_guard(mut self, f: impl Fn(&str) -> bool + 'static) -> Post {
+            self.publish_guard = Some(Box::new(f));
+            self
+        }
+        /// Ignores transitions until `d` has passed since the last accepted one.
+        pub fn with_transition_cooldown(mut self, d: Duration) -> Post {
+            self.transition_cooldown = Some(d);
+            self
+        }
+        pub fn time_until_next_transition(&self) -> Duration {
+            match (self.transition_cooldown, self.history.last()) {
+                (Some(cooldown), Some(last)) => {
+                    let elapsed = self.clock.now().duration_since(last.at).unwrap_or_default();
+                    cooldown.saturating_sub(elapsed)
+                }
+                _ => Duration::ZERO,
+            }
+        }
+        pub fn publish_vetoes(&self) -> u32 {
+            self.publish_vetoes
+        }
+        pub fn with_strip_bom(mut self, enabled: bool) -> Post {
+            self.strip_bom = enabled;
+            self
+        }
+        /// Makes `add_paragraph` skip a paragraph identical to the last one.
+        pub fn with_dedup_paragraphs(mut self, enabled: bool) -> Post {
+            self.dedup_paragraphs = enabled;
+            self
+        }
+        /// Shifts headings in `content()` so the shallowest one becomes `#`,
+        /// keeping their relative nesting.
+        pub fn with_normalized_headings(mut self, enabled: bool) -> Post {
+            self.normalize_headings = enabled;
+            self
+        }
+        /// Shows straight quotes and apostrophes as curly ones in `content()`.
+        pub fn with_smart_quotes(mut self, enabled: bool) -> Post {
+            self.smart_quotes = enabled;
+            self
+        }
+        pub fn with_title_case_headings(mut self, enabled: bool) -> Post {
+            self.title_case_headings = enabled;
+            self
+        }
+        pub fn with_max_avg_sentence_words(mut self, n: usize) -> Post {
+            self.max_avg_sentence_words = Some(n);
+            self
+        }
+        /// Headings such as `## Introduction` that must appear before review is
+        /// requested. A bare name like `Introduction` matches at any level.
+        pub fn with_required_sections(mut self, headings: &[&str]) -> Post {
+            self.required_sections = headings.iter().map(|h| h.to_string()).collect();
+            self
+        }
+        pub fn missing_sections(&self) -> Vec<String> {
+            let present = parse_headings(&self.content);
+            self.required_sections
+                .iter()
+                .filter(|required| match parse_heading(required) {
+                    Some(heading) => !present.contains(&heading),
+                    None => !present.iter().any(|(_, text)| *text == required.trim()),
+                })
+                .cloned()
+                .collect()
+        }
+        pub fn avg_sentence_length(&self) -> f64 {
+            let sentences = split_sentences(&self.content);
+            if sentences.is_empty() {
+                return 0.0;
+            }
+            let words: usize = sentences
+                .iter()
+                .map(|sentence| sentence.split_whitespace().count())
+                .sum();
+            words as f64 / sentences.len() as f64
+        }
+        pub fn with_strip_emoji(mut self, enabled: bool) -> Post {
+            self.strip_emoji = enabled;
+            self
+        }
+        /// Trims surrounding whitespace from the stored content once, at the
+        /// moment the post is published.
+        pub fn with_trim_on_publish(mut self, enabled: bool) -> Post {
+            self.trim_on_publish = enabled;
+            self
+        }
+        pub fn with_embargo(mut self, key: u8) -> Post {
+            self.embargo = Some(key);
+            self
+        }
+        pub fn lift_embargo(&mut self) {
+            self.embargo = None;
+        }
+        /// A fresh draft of the same content that keeps the post type, review
+        /// policy and `content()` view options, but none of the review progress.
+        pub fn clone_as_draft(&self) -> Post {
+            Post {
+                content: self.content.clone(),
+                post_type: self.post_type,
+                required_approvals: self.required_approvals,
+                required_rejections: self.required_rejections,
+                required_roles: self.required_roles.clone(),
+                reviewer_allowlist: self.reviewer_allowlist.clone(),
+                approval_weights: self.approval_weights.clone(),
+                review_quorum: self.review_quorum,
+                redactions: self.redactions.clone(),
+                profanity_filter: self.profanity_filter.clone(),
+                style_replacements: self.style_replacements.clone(),
+                content_transforms: self.content_transforms.clone(),
+                embargo: self.embargo,
+                title_case_headings: self.title_case_headings,
+                strip_emoji: self.strip_emoji,
+                smart_quotes: self.smart_quotes,
+                normalize_headings: self.normalize_headings,
+                ..Post::new().with_clock(Rc::clone(&self.clock))
+            }
+        }
+        pub fn fork(&self) -> Post {
+            Post {
+                state: Some(self.state.as_ref().unwrap().boxed_clone()),
+                ..self.clone_as_draft()
+            }
+        }
+        /// A fresh draft of the same content in `target_lang`, remembering the
+        /// id of the post it translates.
+        pub fn clone_for_translation(&self, target_lang: &str) -> Post {
+            Post {
+                source_id: self.id,
+                ..self.clone_as_draft().with_language(target_lang)
+            }
+        }
+        pub fn source_id(&self) -> Option<u64> {
+            self.source_id
+        }
+        pub fn add_text(&mut self, text: &str) {
+            self.record(Command::AddText(text.to_string()));
+            let text = if self.strip_bom {
+                text.strip_prefix('\u{FEFF}').unwrap_or(text)
             } else {
-                self
+                text
+            };
+            let mut text = Cow::Borrowed(text);
+            if self.normalize_newlines {
+                text = Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"));
+            }
+            let content = self.state.as_ref().unwrap().add_text(&self.content, &text);
+            if content == self.content {
+                return;
+            }
+            self.was_edited = true;
+            let delta = content[self.content.len()..].to_string();
+            self.content = content;
+            for listener in &mut self.text_listeners {
+                listener(&delta);
+            }
+            let event = PostEvent::TextAdded(delta);
+            for subscriber in &self.subscribers {
+                subscriber.notify(&event);
             }
         }
-        fn reject(self: Box<Self>) -> Box<dyn State> {
-            Box::new(Draft {})
+        /// Calls `f` with exactly the text each `add_text` appended, after
+        /// newline normalization and BOM stripping.
+        pub fn on_text_added(&mut self, f: impl FnMut(&str) + 'static) {
+            self.text_listeners.push(Box::new(f));
+        }
+        pub fn clear_text(&mut self) {
+            self.record(Command::ClearText);
+            self.content = self.state.as_ref().unwrap().clear_text(&self.content);
+        }
+        pub fn was_edited(&self) -> bool {
+            self.was_edited
+        }
+        pub fn add_from_reader<R: Read>(&mut self, reader: &mut R) -> io::Result<usize> {
+            if !self.state.as_ref().unwrap().is_editable() {
+                return Ok(0);
+            }
+            let mut text = String::new();
+            reader.read_to_string(&mut text)?;
+            let before = self.content.len();
+            self.add_text(&text);
+            Ok(self.content.len() - before)
+        }
+        pub fn add_paragraph(&mut self, text: &str) {
+            if self.dedup_paragraphs
+                && self
+                    .content
+                    .rsplit("\n\n")
+                    .next()
+                    .is_some_and(|last| last.trim() == text.trim())
+            {
+                return;
+            }
+            if self.content.is_empty() {
+                self.add_text(text);
+            } else {
+                self.add_text(&format!("\n\n{}", text));
+            }
+        }
+        pub fn content(&self) -> Cow<'_, str> {
+            let content = self.unembargoed_content();
+            match self.embargo {
+                Some(key) if !content.is_empty() => Cow::Owned(scramble(&content, key)),
+                _ => content,
+            }
+        }
+        /// The `content()` view before embargo scrambling, which the owned
+        /// extractors such as `headings()` read.
+        fn unembargoed_content(&self) -> Cow<'_, str> {
+            let mut content = Cow::Borrowed(self.visible_content());
+            if content.is_empty() {
+                return content;
+            }
+            for word in &self.redactions {
+                content = Cow::Owned(replace_words(&content, word, |matched| {
+                    "█".repeat(matched.chars().count())
+                }));
+            }
+            for (from, to) in &self.style_replacements {
+                content = Cow::Owned(replace_words(&content, from, |_| to.clone()));
+            }
+            if let Some((words, mask)) = &self.profanity_filter {
+                for word in words {
+                    content = Cow::Owned(replace_words(&content, word, |matched| {
+                        mask.to_string().repeat(matched.chars().count())
+                    }));
+                }
+            }
+            if self.normalize_headings {
+                let top = parse_headings(&content)
+                    .into_iter()
+                    .map(|(level, _)| level)
+                    .min();
+                if let Some(shift) = top.map(|top| top - 1).filter(|shift| *shift > 0) {
+                    content = Cow::Owned(map_headings(&content, |level, text| {
+                        format!("{} {}", "#".repeat((level - shift) as usize), text)
+                    }));
+                }
+            }
+            if self.title_case_headings {
+                content = Cow::Owned(map_headings(&content, |level, text| {
+                    format!("{} {}", "#".repeat(level as usize), title_case(text))
+                }));
+            }
+            if self.strip_emoji {
+                content = Cow::Owned(content.chars().filter(|c| !is_emoji(*c)).collect());
+            }
+            if self.smart_quotes {
+                content = Cow::Owned(smart_quotes(&content));
+            }
+            for transform in &self.content_transforms {
+                content = Cow::Owned(transform(&content));
+            }
+            content
+        }
+        /// Whether `text` contains a word `content()` would redact.
+        fn is_redacted(&self, text: &str) -> bool {
+            self.redactions.iter().any(|word| contains_word(text, word))
+        }
+        fn transition(
+            &mut self,
+            action: Action,
+            f: impl FnOnce(Box<dyn State>, &Post) -> Box<dyn State>,
+        ) {
+            if !self.time_until_next_transition().is_zero() {
+                return;
+            }
+            if let Some(s) = self.state.take() {
+                let from = s.state();
+                let counts = (s.approvals(), s.rejections());
+                let guarded = self.publish_guard.is_some().then(|| s.boxed_clone());
+                let mut next = f(s, self);
+                if let (Some(previous), Some(guard)) = (guarded, &self.publish_guard) {
+                    if next.state() == PostState::Published && !guard(&self.content) {
+                        self.publish_vetoes += 1;
+                        next = previous;
+                    }
+                }
+                let now = self.clock.now();
+                if next.state() != from {
+                    self.state_since = Some(now);
+                }
+                if next.state() != from || (next.approvals(), next.rejections()) != counts {
+                    self.history.push(Transition {
+                        action,
+                        from,
+                        to: next.state(),
+                        at: now,
+                    });
+                }
+                let published =
+                    from != PostState::Published && next.state() == PostState::Published;
+                self.state = Some(next);
+                if published {
+                    self.on_publish();
+                }
+            }
+        }
+        fn on_publish(&mut self) {
+            if self.trim_on_publish {
+                self.content = self.content.trim().to_string();
+            }
+            if self.auto_tag && self.tags.is_empty() {
+                self.tags = self.suggest_tags(AUTO_TAGS);
+            }
+            #[cfg(feature = "webhook")]
+            if let (Some(url), Some(item)) = (&self.webhook, self.to_feed_item()) {
+                let _ = (self.webhook_sender)(url, &item.to_json());
+            }
+        }
+        fn visible_content(&self) -> &str {
+            self.state.as_ref().unwrap().content(self)
+        }
+        pub fn request_review(&mut self) {
+            self.record(Command::RequestReview);
+            if !self.ready_for_review() {
+                return;
+            }
+            let was_pending = self.is_pending();
+            self.transition(Action::RequestReview, |s, _| s.request_review());
+            if !was_pending && self.is_pending() {
+                self.notify_outstanding_roles();
+            }
+        }
+        /// Subscribes `s` to events about the post itself, such as appended text.
+        pub fn subscribe(&mut self, s: Rc<dyn Subscriber>) {
+            self.subscribers.push(s);
+        }
+        /// Subscribes `s` to events addressed to reviewers with `role`, such as
+        /// review requests while that role's approval is still outstanding.
+        pub fn subscribe_role(&mut self, role: &str, s: Rc<dyn Subscriber>) {
+            self.role_subscribers.push((role.to_string(), s));
+        }
+        fn notify_outstanding_roles(&self) {
+            let outstanding = self.outstanding_roles();
+            for (role, subscriber) in &self.role_subscribers {
+                if outstanding.contains(&role.as_str()) {
+                    subscriber.notify(&PostEvent::ReviewRequested { role: role.clone() });
+                }
+            }
+        }
+        fn ready_for_review(&self) -> bool {
+            !self.exceeds_review_word_limit()
+                && self
+                    .max_avg_sentence_words
+                    .is_none_or(|max| self.avg_sentence_length() <= max as f64)
+                && self.missing_sections().is_empty()
+                && self.broken_links().is_empty()
+        }
+        pub fn approve(&mut self) {
+            self.record(Command::Approve);
+            self.approve_unrecorded();
+        }
+        fn approve_unrecorded(&mut self) {
+            self.transition(Action::Approve, |s, post| s.approve(post));
+        }
+        pub fn try_approve(&mut self) -> Result<(), PostError> {
+            if !self.time_until_next_transition().is_zero() {
+                return Err(PostError::CoolingDown);
+            }
+            let vetoes = self.publish_vetoes;
+            self.approve();
+            if self.publish_vetoes > vetoes {
+                Err(PostError::PublishVetoed)
+            } else {
+                Ok(())
+            }
+        }
+        pub fn approve_with_comment(&mut self, comment: &str) {
+            self.record(Command::ApproveWithComment(comment.to_string()));
+            let counts = self.is_pending() && self.time_until_next_transition().is_zero();
+            let vetoes = self.publish_vetoes;
+            self.approve_unrecorded();
+            if counts && self.publish_vetoes == vetoes {
+                self.comments.push(comment.to_string());
+            }
+        }
+        pub fn comments(&self) -> &[String] {
+            &self.comments
+        }
+        pub fn approve_by(&mut self, reviewer: &str) {
+            self.record(Command::ApproveBy(reviewer.to_string()));
+            self.approve_as(reviewer, None);
+        }
+        /// Counts `reviewer`'s approval, and their `role` if given, only when the
+        /// reviewer is allowed, hasn't approved yet and no cooldown is running.
+        fn approve_as(&mut self, reviewer: &str, role: Option<&str>) {
+            let state = self.state.as_ref().unwrap();
+            let ready = self.time_until_next_transition().is_zero();
+            if ready && self.may_review(reviewer) && !state.has_approved(reviewer) {
+                // Recording inside the transition keeps the approver out of the
+                // snapshot a publish guard restores on veto.
+                self.transition(Action::Approve, |s, post| {
+                    if let Some(role) = role {
+                        s.record_role_approval(reviewer, role);
+                    }
+                    s.record_approver(reviewer);
+                    s.approve(post)
+                });
+            }
+        }
+        pub fn reject_by(&mut self, reviewer: &str) {
+            self.record(Command::RejectBy(reviewer.to_string()));
+            let state = self.state.as_ref().unwrap();
+            let ready = self.time_until_next_transition().is_zero();
+            if ready && self.may_review(reviewer) && !state.has_rejected(reviewer) {
+                self.transition(Action::Reject, |s, post| {
+                    s.record_rejecter(reviewer);
+                    s.reject(post)
+                });
+            }
+        }
+        pub fn reviewers_pending(&self) -> Vec<&str> {
+            let state = self.state.as_ref().unwrap();
+            if !state.is_pending() {
+                return Vec::new();
+            }
+            self.reviewer_allowlist
+                .iter()
+                .map(String::as_str)
+                .filter(|name| !state.has_approved(name) && !state.has_rejected(name))
+                .collect()
+        }
+        /// Assigns `name` to review by `deadline`; reassigning moves the deadline.
+        pub fn assign_reviewer(&mut self, name: &str, deadline: SystemTime) {
+            match self.assigned_reviewers.iter_mut().find(|(n, _)| n == name) {
+                Some((_, d)) => *d = deadline,
+                None => self.assigned_reviewers.push((name.to_string(), deadline)),
+            }
+        }
+        /// Assigned reviewers past their deadline who have not approved yet.
+        /// Only pending posts can have overdue reviewers.
+        pub fn overdue_reviewers(&self) -> Vec<&str> {
+            let state = self.state.as_ref().unwrap();
+            if !state.is_pending() {
+                return Vec::new();
+            }
+            let now = self.clock.now();
+            self.assigned_reviewers
+                .iter()
+                .filter(|(name, deadline)| *deadline < now && !state.has_approved(name))
+                .map(|(name, _)| name.as_str())
+                .collect()
+        }
+        pub fn approve_by_role(&mut self, reviewer: &str, role: &str) {
+            self.record(Command::ApproveByRole(
+                reviewer.to_string(),
+                role.to_string(),
+            ));
+            self.approve_as(reviewer, Some(role));
+        }
+        /// Adds the named approvers of `other` that `self` is missing, counting
+        /// each once. This is a no-op unless both posts are pending, and
+        /// anonymous approvals made through `approve` cannot be merged.
+        pub fn merge_approvals_from(&mut self, other: &Post) {
+            if !self.is_pending() || !other.is_pending() {
+                return;
+            }
+            for reviewer in other.state.as_ref().unwrap().approvers() {
+                self.approve_by(&reviewer);
+            }
+        }
+        pub fn outstanding_roles(&self) -> Vec<&str> {
+            self.state.as_ref().unwrap().outstanding_roles(self)
+        }
+        pub fn approvals_needed_from_roles(&self) -> Vec<&str> {
+            if self.is_pending() {
+                self.outstanding_roles()
+            } else {
+                Vec::new()
+            }
+        }
+        pub fn reject(&mut self) {
+            self.record(Command::Reject);
+            self.transition(Action::Reject, |s, post| s.reject(post));
+        }
+        /// Lets the author pull a pending post back into draft, discarding its
+        /// approvals. Unlike `reject`, this is recorded as `CancelReview`.
+        pub fn cancel_review(&mut self) {
+            self.record(Command::CancelReview);
+            self.transition(Action::CancelReview, |s, _| s.cancel_review());
+        }
+        pub fn rejections(&self) -> u8 {
+            self.state.as_ref().unwrap().rejections()
+        }
+        pub fn line_count(&self) -> usize {
+            self.content.lines().count()
+        }
+        pub fn byte_len(&self) -> usize {
+            self.content.len()
+        }
+        pub fn render_with(&self, vars: &HashMap<&str, String>) -> String {
+            let content = self.content();
+            let mut rendered = String::new();
+            let mut rest: &str = &content;
+            while let Some(start) = rest.find("{{") {
+                rendered.push_str(&rest[..start]);
+                let token = &rest[start..];
+                match token.find("}}") {
+                    Some(end) => {
+                        match vars.get(&token[2..end]) {
+                            Some(value) => rendered.push_str(value),
+                            None => rendered.push_str(&token[..end + 2]),
+                        }
+                        rest = &token[end + 2..];
+                    }
+                    None => {
+                        rest = token;
+                        break;
+                    }
+                }
+            }
+            rendered.push_str(rest);
+            rendered
+        }
+        pub fn retry_review(&mut self) -> bool {
+            if self.is_pending() {
+                return false;
+            }
+            self.request_review();
+            self.is_pending()
+        }
+        fn is_pending(&self) -> bool {
+            self.state.as_ref().unwrap().is_pending()
+        }
+        pub fn is_terminal(&self) -> bool {
+            self.state.as_ref().unwrap().is_terminal()
+        }
+        pub fn state(&self) -> PostState {
+            self.state.as_ref().unwrap().state()
+        }
+        /// Actions that would change the state now; none while a transition
+        /// cooldown is running.
+        pub fn available_actions(&self) -> Vec<Action> {
+            if !self.time_until_next_transition().is_zero() {
+                return Vec::new();
+            }
+            let mut actions = self.state.as_ref().unwrap().available_actions();
+            if !self.ready_for_review() {
+                actions.retain(|action| *action != Action::RequestReview);
+            }
+            actions
+        }
+        pub fn preview_transition(&self, action: Action) -> PostState {
+            let s = self.state.as_ref().unwrap().boxed_clone();
+            let current = s.state();
+            if !self.time_until_next_transition().is_zero() {
+                return current;
+            }
+            let next = match action {
+                Action::RequestReview if !self.ready_for_review() => current,
+                Action::RequestReview => s.request_review().state(),
+                Action::Approve => s.approve(self).state(),
+                Action::Reject => s.reject(self).state(),
+                Action::CancelReview => s.cancel_review().state(),
+            };
+            let vetoed = self
+                .publish_guard
+                .as_ref()
+                .is_some_and(|guard| !guard(&self.content));
+            if next == PostState::Published && vetoed {
+                current
+            } else {
+                next
+            }
+        }
+        /// A 64-bit FNV-1a hash of the content, stable across builds and platforms.
+        pub fn checksum(&self) -> u64 {
+            self.content.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+        }
+        pub fn verify_checksum(&self, expected: u64) -> bool {
+            self.checksum() == expected
+        }
+        pub fn wrapped(&self, width: usize) -> String {
+            let width = width.max(1);
+            let mut lines = Vec::new();
+            for paragraph in self.content().lines() {
+                let mut line = String::new();
+                for mut word in paragraph.split_whitespace() {
+                    while word.chars().count() > width {
+                        if !line.is_empty() {
+                            lines.push(std::mem::take(&mut line));
+                        }
+                        let (split, _) = word.char_indices().nth(width).unwrap();
+                        lines.push(word[..split].to_string());
+                        word = &word[split..];
+                    }
+                    if line.is_empty() {
+                        line.push_str(word);
+                    } else if line.chars().count() + 1 + word.chars().count() <= width {
+                        line.push(' ');
+                        line.push_str(word);
+                    } else {
+                        lines.push(std::mem::replace(&mut line, word.to_string()));
+                    }
+                }
+                lines.push(line);
+            }
+            lines.join("\n")
+        }
+        /// Consumes the post, moving its content into the `rust_way` stage that
+        /// matches its current state and the approvals it still needs, counting
+        /// each outstanding role as one. `rust_way` stages need at most two
+        /// approvals and know nothing of roles, weights or quorums, so those
+        /// are lost; more than two missing approvals map to `PendingReview`.
+        pub fn into_rust_way(self) -> RustWayPost {
+            let missing = self.missing_approvals();
+            let content = self.content;
+            match self.state.as_ref().unwrap().state() {
+                PostState::Draft => RustWayPost::Draft(rust_way::DraftPost { content }),
+                PostState::PendingReview if missing > 1 => {
+                    RustWayPost::PendingReview(rust_way::PendingReviewPost { content })
+                }
+                PostState::PendingReview => {
+                    RustWayPost::Approved(rust_way::ApprovedPendingReviewPost { content })
+                }
+                PostState::Published => RustWayPost::Published(rust_way::Post { content }),
+            }
+        }
+        /// Approvals a pending post needs before it publishes: the shortfall in
+        /// its approval score, or its outstanding roles if there are more.
+        fn missing_approvals(&self) -> u32 {
+            let state = self.state.as_deref().unwrap();
+            let score = match self.approval_score(state) {
+                Some((current, threshold)) => threshold.saturating_sub(current),
+                None => u32::MAX,
+            };
+            score.max(self.outstanding_roles().len() as u32)
+        }
+        /// Renders YAML front-matter for the metadata that is set, quoting text
+        /// values, followed by the visible content, which is empty until the
+        /// post is published.
+        pub fn to_markdown_with_frontmatter(&self) -> String {
+            let mut markdown = String::from("---\n");
+            if let Some(id) = self.id {
+                markdown.push_str(&format!("id: {}\n", id));
+            }
+            if !self.title.is_empty() {
+                markdown.push_str(&format!("title: {}\n", json_string(&self.title)));
+            }
+            if !self.author.is_empty() {
+                markdown.push_str(&format!("author: {}\n", json_string(&self.author)));
+            }
+            if !self.language.is_empty() {
+                markdown.push_str(&format!("language: {}\n", json_string(&self.language)));
+            }
+            if !self.tags.is_empty() {
+                let tags: Vec<String> = self.tags.iter().map(|tag| json_string(tag)).collect();
+                markdown.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+            }
+            markdown.push_str("---\n");
+            markdown.push_str(&self.content());
+            markdown
+        }
+        /// Shortens the visible content to at most `max_chars` characters,
+        /// counting the ellipsis against the limit. An ellipsis longer than
+        /// the limit is itself cut down to fit.
+        pub fn summary_with(&self, max_chars: usize, ellipsis: &str) -> String {
+            let content = self.content();
+            if content.chars().count() <= max_chars {
+                return content.into_owned();
+            }
+            if ellipsis.chars().count() >= max_chars {
+                return ellipsis.chars().take(max_chars).collect();
+            }
+            let keep = max_chars - ellipsis.chars().count();
+            let summary: String = content.chars().take(keep).collect();
+            format!("{}{}", summary.trim_end(), ellipsis)
+        }
+        pub fn apply(&mut self, cmd: Command) {
+            match cmd {
+                Command::AddText(text) => self.add_text(&text),
+                Command::ClearText => self.clear_text(),
+                Command::RequestReview => self.request_review(),
+                Command::Approve => self.approve(),
+                Command::ApproveWithComment(comment) => self.approve_with_comment(&comment),
+                Command::ApproveBy(reviewer) => self.approve_by(&reviewer),
+                Command::ApproveByRole(reviewer, role) => self.approve_by_role(&reviewer, &role),
+                Command::Reject => self.reject(),
+                Command::RejectBy(reviewer) => self.reject_by(&reviewer),
+                Command::CancelReview => self.cancel_review(),
+                Command::Revise(content) => self.revise(&content),
+                Command::RevertToRevision(version) => {
+                    self.revert_to_revision(version);
+                }
+            }
+        }
+        /// Starts logging every text edit, review request, approval, rejection,
+        /// cancellation and revision, including those made by convenience methods.
+        /// Named and commented approvals are logged as such so replay matches.
+        pub fn start_recording(&mut self) {
+            self.recording.get_or_insert_with(Vec::new);
+        }
+        pub fn recorded_commands(&self) -> &[Command] {
+            self.recording.as_deref().unwrap_or(&[])
+        }
+        fn record(&mut self, cmd: Command) {
+            if let Some(recording) = self.recording.as_mut() {
+                recording.push(cmd);
+            }
+        }
+        pub fn apply_all(&mut self, cmds: impl IntoIterator<Item = Command>) {
+            for cmd in cmds {
+                self.apply(cmd);
+            }
+        }
+        pub fn stats(&self) -> ContentStats {
+            let words = self.content.split_whitespace().count();
+            ContentStats {
+                chars: self.content.chars().count(),
+                bytes: self.byte_len(),
+                words,
+                lines: self.line_count(),
+                reading_time_minutes: words.div_ceil(WORDS_PER_MINUTE),
+            }
+        }
+        pub fn history(&self) -> &[Transition] {
+            &self.history
+        }
+        pub fn history_iter(&self) -> impl Iterator<Item = &Transition> {
+            self.history.iter()
+        }
+        /// How many times the post entered `state`, counting creation as the
+        /// first entry into `Draft`.
+        pub fn state_visit_count(&self, state: PostState) -> u32 {
+            let initial = (state == PostState::Draft) as u32;
+            let entries = self
+                .history
+                .iter()
+                .filter(|transition| transition.from != transition.to && transition.to == state)
+                .count() as u32;
+            initial + entries
+        }
+        /// Appends `other`'s history and keeps the combined log in chronological
+        /// order; entries with equal timestamps keep `self`'s first.
+        pub fn merge_history_from(&mut self, other: &Post) {
+            self.history.extend(other.history.iter().cloned());
+            self.history.sort_by_key(|transition| transition.at);
+        }
+        /// Estimates publication as `avg_approval_interval` per approval still
+        /// missing from the approval score, starting from now; role
+        /// requirements are not considered. `None` while a quorum rule has
+        /// nobody assigned or when the estimate overflows.
+        pub fn estimated_publish_time(
+            &self,
+            avg_approval_interval: Duration,
+        ) -> Option<SystemTime> {
+            if !self.is_pending() {
+                return None;
+            }
+            let (current, threshold) = self.approval_score(self.state.as_deref().unwrap())?;
+            let remaining = threshold.saturating_sub(current);
+            let wait = avg_approval_interval.checked_mul(remaining)?;
+            self.clock.now().checked_add(wait)
+        }
+        /// Splits the published content, as stored, on blank lines. Paragraphs
+        /// containing a redacted word are left out.
+        pub fn paragraphs(&self) -> Vec<&str> {
+            let content = self.visible_content();
+            let mut paragraphs = Vec::new();
+            let mut start = None;
+            let mut end = 0;
+            let mut offset = 0;
+            for line in content.split_inclusive('\n') {
+                if line.trim().is_empty() {
+                    if let Some(start) = start.take() {
+                        paragraphs.push(content[start..end].trim());
+                    }
+                } else {
+                    start.get_or_insert(offset);
+                    end = offset + line.len();
+                }
+                offset += line.len();
+            }
+            if let Some(start) = start {
+                paragraphs.push(content[start..end].trim());
+            }
+            paragraphs.retain(|paragraph| !self.is_redacted(paragraph));
+            paragraphs
+        }
+        pub fn headings(&self) -> Vec<(u8, String)> {
+            parse_headings(&self.unembargoed_content())
+                .into_iter()
+                .map(|(level, text)| (level, text.to_string()))
+                .collect()
+        }
+        /// Replaces the content of a published post, keeping the previous
+        /// version. Drafts and pending posts are edited with `add_text` instead.
+        pub fn revise(&mut self, content: &str) {
+            let _ = self.try_revise(content);
+        }
+        pub fn try_revise(&mut self, content: &str) -> Result<(), PostError> {
+            self.record(Command::Revise(content.to_string()));
+            self.revise_unrecorded(content)
+        }
+        fn revise_unrecorded(&mut self, content: &str) -> Result<(), PostError> {
+            if self.state() != PostState::Published {
+                return Err(PostError::NotPublished);
+            }
+            if self.revisions_remaining() == Some(0) {
+                return Err(PostError::RevisionLimitReached);
+            }
+            let previous = std::mem::replace(&mut self.content, content.to_string());
+            self.revisions.push(previous);
+            Ok(())
+        }
+        /// Restores the content of `version` as a new revision. Returns false for
+        /// versions that never existed or when the revision can't be made.
+        pub fn revert_to_revision(&mut self, version: u32) -> bool {
+            self.record(Command::RevertToRevision(version));
+            if version == 0 || version > self.version() {
+                return false;
+            }
+            let content = match self.revisions.get(version as usize - 1) {
+                Some(content) => content.clone(),
+                None => self.content.clone(),
+            };
+            self.revise_unrecorded(&content).is_ok()
+        }
+        pub fn with_max_revisions(mut self, n: u32) -> Post {
+            self.max_revisions = Some(n);
+            self
+        }
+        /// Revisions still allowed, or `None` when revisions are unlimited.
+        pub fn revisions_remaining(&self) -> Option<u32> {
+            self.max_revisions
+                .map(|max| max.saturating_sub(self.revisions.len() as u32))
+        }
+        /// The published version number, starting at 1 and increasing with
+        /// every revision, or 0 before publication.
+        pub fn version(&self) -> u32 {
+            if self.state() == PostState::Published {
+                self.revisions.len() as u32 + 1
+            } else {
+                0
+            }
+        }
+        pub fn diff_with_previous(&self) -> Vec<DiffOp> {
+            match self.revisions.last() {
+                Some(previous) => diff_lines(previous, &self.content),
+                None => Vec::new(),
+            }
+        }
+        /// Extracts bare `http(s)://` URLs and Markdown link targets from the
+        /// published content, without validating them. Links containing a
+        /// redacted word are left out.
+        pub fn links(&self) -> Vec<&str> {
+            let mut links = extract_links(self.visible_content());
+            links.retain(|link| !self.is_redacted(link));
+            links
+        }
+        /// Checks every link in the draft with `f`; review requests are refused
+        /// while any link fails.
+        pub fn with_link_validator(mut self, f: impl Fn(&str) -> bool + 'static) -> Post {
+            self.link_validator = Some(Box::new(f));
+            self
+        }
+        pub fn broken_links(&self) -> Vec<&str> {
+            match &self.link_validator {
+                Some(valid) => extract_links(&self.content)
+                    .into_iter()
+                    .filter(|link| !valid(link))
+                    .collect(),
+                None => Vec::new(),
+            }
+        }
+        /// Renders the visible content as HTML. Supports ATX headings,
+        /// paragraphs, GitHub-style pipe tables, links and images.
+        pub fn content_html(&self) -> String {
+            render_html(&self.unembargoed_content(), self.base_url.as_deref())
+        }
+        /// Prefixes relative link and image targets in `content_html()` with
+        /// `base`; URLs with a scheme such as `mailto:`, protocol-relative URLs
+        /// and fragments are kept.
+        pub fn with_base_url(mut self, base: &str) -> Post {
+            self.base_url = Some(base.to_string());
+            self
+        }
+        /// Counts bracketed-number citations such as `[1]` in the stored content,
+        /// whatever the state. Links like `[1](url)` are not citations.
+        pub fn citation_count(&self) -> usize {
+            let mut count = 0;
+            let mut rest = self.content.as_str();
+            while let Some(start) = rest.find('[') {
+                rest = &rest[start + 1..];
+                let digits = rest.chars().take_while(char::is_ascii_digit).count();
+                if digits > 0
+                    && rest[digits..].starts_with(']')
+                    && !rest[digits + 1..].starts_with('(')
+                {
+                    count += 1;
+                }
+            }
+            count
+        }
+        /// `(language, code)` for each fenced code block in the visible
+        /// content. A block left open runs to the end of the post.
+        pub fn code_blocks(&self) -> Vec<(Option<String>, String)> {
+            let mut blocks = Vec::new();
+            let content = self.unembargoed_content();
+            let mut open: Option<(Option<String>, Vec<&str>)> = None;
+            for line in content.lines() {
+                let fence = line.trim_start().strip_prefix("```");
+                match (&mut open, fence) {
+                    (None, Some(info)) => {
+                        let language = info.split_whitespace().next().map(str::to_string);
+                        open = Some((language, Vec::new()));
+                    }
+                    (Some(_), Some(rest)) if rest.trim().is_empty() => {
+                        let (language, code) = open.take().unwrap();
+                        blocks.push((language, code.join("\n")));
+                    }
+                    (Some((_, code)), _) => code.push(line),
+                    (None, None) => {}
+                }
+            }
+            if let Some((language, code)) = open {
+                blocks.push((language, code.join("\n")));
+            }
+            blocks
+        }
+        /// `(alt, url)` for each Markdown image `![alt](url)` in the visible
+        /// content.
+        pub fn images(&self) -> Vec<(String, String)> {
+            let content = self.unembargoed_content();
+            let mut images = Vec::new();
+            let mut rest: &str = &content;
+            while let Some(start) = rest.find("![") {
+                rest = &rest[start + 2..];
+                let Some((alt, after)) = rest.split_once("](") else {
+                    break;
+                };
+                let Some((url, after)) = after.split_once(')') else {
+                    break;
+                };
+                if !alt.contains(']') && !url.trim().is_empty() {
+                    images.push((alt.to_string(), url.trim().to_string()));
+                    rest = after;
+                }
+            }
+            images
+        }
+        /// `[^marker]: text` definitions in the visible content.
+        pub fn footnotes(&self) -> Vec<(String, String)> {
+            self.unembargoed_content()
+                .lines()
+                .filter_map(|line| {
+                    let (marker, text) = line.trim_start().strip_prefix("[^")?.split_once("]:")?;
+                    Some((marker.to_string(), text.trim().to_string()))
+                })
+                .collect()
+        }
+        /// Markers referenced as `[^marker]` in the visible content that have
+        /// no definition, in order of first reference.
+        pub fn undefined_footnotes(&self) -> Vec<String> {
+            let defined: Vec<String> = self
+                .footnotes()
+                .into_iter()
+                .map(|(marker, _)| marker)
+                .collect();
+            let mut undefined: Vec<String> = Vec::new();
+            for line in self.unembargoed_content().lines() {
+                let mut rest = line;
+                while let Some(start) = rest.find("[^") {
+                    rest = &rest[start + 2..];
+                    let Some(end) = rest.find(']') else {
+                        break;
+                    };
+                    let marker = &rest[..end];
+                    let is_definition = rest[end + 1..].starts_with(':');
+                    if !is_definition
+                        && !marker.is_empty()
+                        && !defined.iter().any(|d| d == marker)
+                        && !undefined.iter().any(|u| u == marker)
+                    {
+                        undefined.push(marker.to_string());
+                    }
+                    rest = &rest[end + 1..];
+                }
+            }
+            undefined
+        }
+        /// Splits the published content, as stored, into sentences ending in
+        /// `.`, `!` or `?` followed by whitespace. Abbreviations such as "e.g. "
+        /// also end a sentence. Sentences containing a redacted word are left out.
+        pub fn sentences(&self) -> Vec<&str> {
+            let mut sentences = split_sentences(self.visible_content());
+            sentences.retain(|sentence| !self.is_redacted(sentence));
+            sentences
+        }
+        pub fn highlight(&self, term: &str, open: &str, close: &str) -> String {
+            replace_words(&self.content(), term, |matched| {
+                format!("{}{}{}", open, matched, close)
+            })
+        }
+        /// Pairs each heading with a URL-safe id; repeated ids get `-1`, `-2`, ...
+        pub fn heading_anchors(&self) -> Vec<(String, String)> {
+            let mut anchors: Vec<(String, String)> = Vec::new();
+            for (_, text) in self.headings() {
+                let base = slugify(&text);
+                let mut id = base.clone();
+                let mut suffix = 0;
+                while anchors.iter().any(|(taken, _)| *taken == id) {
+                    suffix += 1;
+                    id = format!("{}-{}", base, suffix);
+                }
+                anchors.push((id, text));
+            }
+            anchors
+        }
+        /// A nested Markdown list linking to each heading, indented relative to
+        /// the shallowest heading level.
+        pub fn table_of_contents(&self) -> String {
+            let headings = self.headings();
+            let top = headings.iter().map(|(level, _)| *level).min().unwrap_or(1);
+            headings
+                .iter()
+                .zip(self.heading_anchors())
+                .map(|((level, _), (id, text))| {
+                    let indent = "  ".repeat((level - top) as usize);
+                    format!("{}- [{}](#{})\n", indent, text, id)
+                })
+                .collect()
+        }
+        pub fn add_tag(&mut self, tag: &str) {
+            if !self.tags.iter().any(|t| t == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+        pub fn tags(&self) -> &[String] {
+            &self.tags
+        }
+        /// Tags the post with its top suggested tags when it is published,
+        /// unless tags were already added by hand.
+        pub fn with_auto_tag(mut self, enabled: bool) -> Post {
+            self.auto_tag = enabled;
+            self
+        }
+        pub fn with_dictionary(mut self, words: HashSet<String>) -> Post {
+            self.dictionary = Some(words.iter().map(|word| word.to_lowercase()).collect());
+            self
+        }
+        /// Words in the draft missing from the dictionary, once each in order
+        /// of first appearance. Purely informational; review is not blocked.
+        pub fn unknown_words(&self) -> Vec<String> {
+            let Some(dictionary) = &self.dictionary else {
+                return Vec::new();
+            };
+            let mut unknown: Vec<String> = Vec::new();
+            for word in words(&self.content) {
+                if !word.chars().all(|c| c.is_ascii_digit())
+                    && !dictionary.contains(&word)
+                    && !unknown.contains(&word)
+                {
+                    unknown.push(word);
+                }
+            }
+            unknown
+        }
+        /// Each keyword's share of all words in the draft, matched
+        /// case-insensitively; 0.0 for every keyword when there are no words.
+        pub fn keyword_density(&self, keywords: &[&str]) -> Vec<(String, f64)> {
+            let all: Vec<String> = words(&self.content).collect();
+            keywords
+                .iter()
+                .map(|keyword| {
+                    let keyword = keyword.to_lowercase();
+                    let hits = all.iter().filter(|word| **word == keyword).count();
+                    let density = if all.is_empty() {
+                        0.0
+                    } else {
+                        hits as f64 / all.len() as f64
+                    };
+                    (keyword, density)
+                })
+                .collect()
+        }
+        /// Suggests the `n` most frequent words that are not English stopwords,
+        /// breaking ties by first appearance.
+        pub fn suggest_tags(&self, n: usize) -> Vec<String> {
+            let mut counts: Vec<(String, usize)> = Vec::new();
+            for word in words(&self.content) {
+                if word.chars().count() < 2 || STOPWORDS.contains(&word.as_str()) {
+                    continue;
+                }
+                match counts.iter_mut().find(|(seen, _)| *seen == word) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((word, 1)),
+                }
+            }
+            counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+            counts.into_iter().take(n).map(|(word, _)| word).collect()
+        }
+        /// The Flesch reading-ease score of the content, or 0 when it is empty.
+        /// Syllables are estimated as groups of consecutive vowels (including
+        /// `y`), ignoring a silent final `e`, with at least one per word.
+        pub fn reading_ease(&self) -> f64 {
+            let words: Vec<String> = words(&self.content).collect();
+            if words.is_empty() {
+                return 0.0;
+            }
+            let sentences = split_sentences(&self.content).len().max(1) as f64;
+            let syllables: usize = words.iter().map(|word| count_syllables(word)).sum();
+            let words = words.len() as f64;
+            206.835 - 1.015 * (words / sentences) - 84.6 * (syllables as f64 / words)
+        }
+    }
+
+    impl Default for Post {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub fn bulk_approve(posts: &mut [Post]) -> usize {
+        bulk_apply(posts, Post::approve)
+    }
+
+    pub fn bulk_request_review(posts: &mut [Post]) -> usize {
+        bulk_apply(posts, Post::request_review)
+    }
+
+    /// Orders a feed with sticky posts first, each group newest first.
+    /// Unpublished posts go last.
+    pub fn sort_feed(posts: &mut [Post]) {
+        posts.sort_by_key(|post| (Reverse(post.sticky), Reverse(post.published_at())));
+    }
+
+    pub fn purge_expired(posts: &mut Vec<Post>) {
+        posts.retain(|post| !post.is_expired());
+    }
+
+    /// Serializes posts to a JSON array with their metadata, type, review
+    /// policy (thresholds, roles, allowlist, weights, quorum and assigned
+    /// reviewers), timestamps, content and revisions, state and review
+    /// progress. Other settings, such as guards and hooks, are not kept.
+    pub fn export_all(posts: &[Post]) -> String {
+        let posts: Vec<String> = posts.iter().map(export_post).collect();
+        format!("[{}]", posts.join(","))
+    }
+
+    /// Reads posts back from `export_all` output.
+    pub fn import_all(json: &str) -> Result<Vec<Post>, PostError> {
+        let mut parser = JsonParser {
+            rest: json,
+            depth: 0,
+        };
+        match parser.document() {
+            Some(Json::Array(posts)) => posts.iter().map(import_post).collect(),
+            _ => Err(PostError::InvalidExport),
+        }
+    }
+
+    fn export_post(post: &Post) -> String {
+        let state = post.state.as_deref().unwrap();
+        let strings = |strings: &[String]| {
+            let strings: Vec<String> = strings.iter().map(|text| json_string(text)).collect();
+            format!("[{}]", strings.join(","))
+        };
+        let state_name = match state.state() {
+            PostState::Draft => "draft",
+            PostState::PendingReview => "pending_review",
+            PostState::Published => "published",
+        };
+        let post_type = match post.post_type {
+            PostType::Article => "article",
+            PostType::Note => "note",
+            PostType::Announcement => "announcement",
+        };
+        let role_approvals: Vec<String> = state
+            .role_approvals()
+            .iter()
+            .map(|(reviewer, role)| format!("[{},{}]", json_string(reviewer), json_string(role)))
+            .collect();
+        let approval_weights: Vec<String> = post
+            .approval_weights
+            .iter()
+            .map(|(reviewer, weight)| format!("[{},{}]", json_string(reviewer), weight))
+            .collect();
+        let assigned_reviewers: Vec<String> = post
+            .assigned_reviewers
+            .iter()
+            .map(|(reviewer, deadline)| {
+                format!(
+                    "[{},{}]",
+                    json_string(reviewer),
+                    nanos_since_epoch(*deadline)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"id\":{},\"title\":{},\"author\":{},\"language\":{},\"tags\":{},\"sticky\":{},\
+             \"post_type\":\"{}\",\"required_approvals\":{},\"required_rejections\":{},\"required_roles\":{},\
+             \"reviewer_allowlist\":{},\"approval_weights\":[{}],\"review_quorum\":{},\"assigned_reviewers\":[{}],\
+             \"created_at\":{},\"state_since\":{},\
+             \"content\":{},\"revisions\":{},\"state\":\"{}\",\"approvals\":{},\"rejections\":{},\
+             \"approvers\":{},\"rejecters\":{},\"role_approvals\":[{}]}}",
+            post.id.map_or(String::from("null"), |id| id.to_string()),
+            json_string(&post.title),
+            json_string(&post.author),
+            json_string(&post.language),
+            strings(&post.tags),
+            post.sticky,
+            post_type,
+            post.required_approvals,
+            post.required_rejections,
+            strings(&post.required_roles),
+            strings(&post.reviewer_allowlist),
+            approval_weights.join(","),
+            post.review_quorum
+                .map_or(String::from("null"), |fraction| fraction.to_string()),
+            assigned_reviewers.join(","),
+            nanos_since_epoch(post.created_at),
+            post.state_since
+                .map_or(String::from("null"), |since| nanos_since_epoch(since)
+                    .to_string()),
+            json_string(&post.content),
+            strings(&post.revisions),
+            state_name,
+            state.approvals(),
+            state.rejections(),
+            strings(&state.approvers()),
+            strings(&state.rejecters()),
+            role_approvals.join(",")
+        )
+    }
+
+    /// Export timestamps are whole nanoseconds since the Unix epoch.
+    fn nanos_since_epoch(time: SystemTime) -> u64 {
+        time.duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64)
+    }
+
+    fn import_post(json: &Json) -> Result<Post, PostError> {
+        let field = |name: &str| json.get(name).ok_or(PostError::InvalidExport);
+        let text = |name: &str| field(name)?.as_str().ok_or(PostError::InvalidExport);
+        let number = |name: &str| field(name)?.as_u64().ok_or(PostError::InvalidExport);
+        let count = |name: &str| u8::try_from(number(name)?).map_err(|_| PostError::InvalidExport);
+        let strings = |name: &str| field(name)?.as_strings().ok_or(PostError::InvalidExport);
+        let time = |nanos: u64| SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos);
+        let pairs = |name: &str| match field(name)? {
+            Json::Array(pairs) => pairs
+                .iter()
+                .map(|pair| match pair {
+                    Json::Array(items) => match items.as_slice() {
+                        [Json::String(name), value] => Ok((name.clone(), value)),
+                        _ => Err(PostError::InvalidExport),
+                    },
+                    _ => Err(PostError::InvalidExport),
+                })
+                .collect::<Result<Vec<_>, _>>(),
+            _ => Err(PostError::InvalidExport),
+        };
+
+        let mut post = Post::new()
+            .with_title(text("title")?)
+            .with_author(text("author")?)
+            .with_language(text("language")?);
+        post.id = match field("id")? {
+            Json::Null => None,
+            id => Some(id.as_u64().ok_or(PostError::InvalidExport)?),
+        };
+        post.tags = strings("tags")?;
+        post.sticky = field("sticky")?.as_bool().ok_or(PostError::InvalidExport)?;
+        post.post_type = match text("post_type")? {
+            "article" => PostType::Article,
+            "note" => PostType::Note,
+            "announcement" => PostType::Announcement,
+            _ => return Err(PostError::InvalidExport),
+        };
+        post.required_approvals = count("required_approvals")?;
+        post.required_rejections = count("required_rejections")?;
+        post.required_roles = strings("required_roles")?;
+        post.reviewer_allowlist = strings("reviewer_allowlist")?;
+        post.approval_weights = pairs("approval_weights")?
+            .into_iter()
+            .map(|(reviewer, weight)| {
+                let weight = weight.as_u64().and_then(|w| u8::try_from(w).ok());
+                weight
+                    .map(|weight| (reviewer, weight))
+                    .ok_or(PostError::InvalidExport)
+            })
+            .collect::<Result<_, _>>()?;
+        post.review_quorum = match field("review_quorum")? {
+            Json::Null => None,
+            fraction => Some(fraction.as_f64().ok_or(PostError::InvalidExport)?),
+        };
+        post.assigned_reviewers = pairs("assigned_reviewers")?
+            .into_iter()
+            .map(|(reviewer, deadline)| {
+                let deadline = deadline.as_u64().ok_or(PostError::InvalidExport)?;
+                Ok((reviewer, time(deadline)))
+            })
+            .collect::<Result<_, _>>()?;
+        post.created_at = time(number("created_at")?);
+        post.state_since = match field("state_since")? {
+            Json::Null => None,
+            since => Some(time(since.as_u64().ok_or(PostError::InvalidExport)?)),
+        };
+        post.content = text("content")?.to_string();
+        post.revisions = strings("revisions")?;
+        let role_approvals = match field("role_approvals")? {
+            Json::Array(pairs) => pairs
+                .iter()
+                .map(|pair| match pair.as_strings().as_deref() {
+                    Some([reviewer, role]) => Ok((reviewer.clone(), role.clone())),
+                    _ => Err(PostError::InvalidExport),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err(PostError::InvalidExport),
+        };
+        post.state = Some(match text("state")? {
+            "draft" => Box::new(Draft {}),
+            "pending_review" => Box::new(PendingReview {
+                approvals: RefCell::new(count("approvals")?),
+                rejections: RefCell::new(count("rejections")?),
+                role_approvals: RefCell::new(role_approvals),
+                approvers: RefCell::new(strings("approvers")?),
+                rejecters: RefCell::new(strings("rejecters")?),
+            }),
+            "published" => Box::new(Published {}),
+            _ => return Err(PostError::InvalidExport),
+        });
+        Ok(post)
+    }
+
+    enum Json {
+        Null,
+        Bool(bool),
+        Number(u64),
+        Fraction(f64),
+        String(String),
+        Array(Vec<Json>),
+        Object(Vec<(String, Json)>),
+    }
+
+    impl Json {
+        fn get(&self, key: &str) -> Option<&Json> {
+            match self {
+                Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+                _ => None,
+            }
+        }
+        fn as_str(&self) -> Option<&str> {
+            match self {
+                Json::String(text) => Some(text),
+                _ => None,
+            }
+        }
+        fn as_bool(&self) -> Option<bool> {
+            match self {
+                Json::Bool(value) => Some(*value),
+                _ => None,
+            }
+        }
+        fn as_u64(&self) -> Option<u64> {
+            match self {
+                Json::Number(n) => Some(*n),
+                _ => None,
+            }
+        }
+        fn as_f64(&self) -> Option<f64> {
+            match self {
+                Json::Number(n) => Some(*n as f64),
+                Json::Fraction(f) => Some(*f),
+                _ => None,
+            }
+        }
+        fn as_strings(&self) -> Option<Vec<String>> {
+            match self {
+                Json::Array(items) => items
+                    .iter()
+                    .map(|item| item.as_str().map(str::to_string))
+                    .collect(),
+                _ => None,
+            }
+        }
+    }
+
+    /// Reads the JSON subset `export_all` writes: objects, arrays, strings,
+    /// non-negative integers and decimals, booleans and `null`, nested at most
+    /// `MAX_JSON_DEPTH` deep.
+    struct JsonParser<'a> {
+        rest: &'a str,
+        depth: usize,
+    }
+
+    const MAX_JSON_DEPTH: usize = 16;
+
+    impl JsonParser<'_> {
+        fn document(&mut self) -> Option<Json> {
+            let value = self.value()?;
+            self.rest.trim_start().is_empty().then_some(value)
+        }
+        fn eat(&mut self, token: char) -> bool {
+            match self.rest.trim_start().strip_prefix(token) {
+                Some(rest) => {
+                    self.rest = rest;
+                    true
+                }
+                None => false,
+            }
+        }
+        fn value(&mut self) -> Option<Json> {
+            self.rest = self.rest.trim_start();
+            match self.rest.chars().next()? {
+                '{' => self
+                    .sequence('{', '}', |parser| {
+                        let key = parser.string()?;
+                        parser.eat(':').then_some(())?;
+                        Some((key, parser.value()?))
+                    })
+                    .map(Json::Object),
+                '[' => self.sequence('[', ']', Self::value).map(Json::Array),
+                '"' => self.string().map(Json::String),
+                'n' => {
+                    self.rest = self.rest.strip_prefix("null")?;
+                    Some(Json::Null)
+                }
+                't' => {
+                    self.rest = self.rest.strip_prefix("true")?;
+                    Some(Json::Bool(true))
+                }
+                'f' => {
+                    self.rest = self.rest.strip_prefix("false")?;
+                    Some(Json::Bool(false))
+                }
+                _ => {
+                    let end = self
+                        .rest
+                        .find(|c: char| !c.is_ascii_digit() && c != '.')
+                        .unwrap_or(self.rest.len());
+                    let (number, rest) = self.rest.split_at(end);
+                    self.rest = rest;
+                    if number.contains('.') {
+                        number.parse().ok().map(Json::Fraction)
+                    } else {
+                        number.parse().ok().map(Json::Number)
+                    }
+                }
+            }
+        }
+        fn sequence<T>(
+            &mut self,
+            open: char,
+            close: char,
+            mut item: impl FnMut(&mut Self) -> Option<T>,
+        ) -> Option<Vec<T>> {
+            if self.depth == MAX_JSON_DEPTH {
+                return None;
+            }
+            self.eat(open).then_some(())?;
+            self.depth += 1;
+            let mut items = Vec::new();
+            if !self.eat(close) {
+                loop {
+                    items.push(item(self)?);
+                    if self.eat(close) {
+                        break;
+                    }
+                    self.eat(',').then_some(())?;
+                }
+            }
+            self.depth -= 1;
+            Some(items)
+        }
+        fn string(&mut self) -> Option<String> {
+            self.eat('"').then_some(())?;
+            let mut text = String::new();
+            let mut chars = self.rest.char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.rest = &self.rest[i + 1..];
+                        return Some(text);
+                    }
+                    '\\' => match chars.next()?.1 {
+                        'n' => text.push('\n'),
+                        'r' => text.push('\r'),
+                        't' => text.push('\t'),
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            text.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                        }
+                        escaped => text.push(escaped),
+                    },
+                    c => text.push(c),
+                }
+            }
+            None
+        }
+    }
+
+    /// Imports legacy `(title, markdown_body)` pairs as already-published posts,
+    /// without running them through review.
+    pub fn import_markdown(files: &[(&str, &str)]) -> Vec<Post> {
+        files
+            .iter()
+            .map(|(title, body)| {
+                let mut post = Post::new().with_title(title);
+                post.add_text(body);
+                post.state = Some(Box::new(Published {}));
+                post
+            })
+            .collect()
+    }
+
+    fn bulk_apply(posts: &mut [Post], action: impl Fn(&mut Post)) -> usize {
+        let mut transitioned = 0;
+        for post in posts.iter_mut() {
+            let from = post.state();
+            action(post);
+            if post.state() != from {
+                transitioned += 1;
+            }
+        }
+        transitioned
+    }
+
+    pub mod testing {
+        use super::{Post, PostState};
+
+        /// Panics if `post` is in an internally inconsistent state.
+        pub fn assert_invariants(post: &Post) {
+            let state = post.state.as_ref().expect("post has no state");
+            match state.state() {
+                PostState::Published => {
+                    assert_eq!(
+                        post.content,
+                        post.visible_content(),
+                        "published content must be visible"
+                    );
+                    assert!(
+                        post.outstanding_roles().is_empty(),
+                        "published posts have no outstanding roles"
+                    );
+                }
+                _ => assert!(
+                    post.visible_content().is_empty(),
+                    "content must only be visible once published"
+                ),
+            }
+            if state.state() != PostState::PendingReview {
+                assert_eq!(0, state.approvals(), "approvals only count while pending");
+                assert_eq!(0, state.rejections(), "rejections only count while pending");
+            }
+            assert_eq!(
+                state.state() == PostState::PendingReview,
+                post.is_pending(),
+                "only pending posts are pending"
+            );
+        }
+    }
+
+    const WORDS_PER_MINUTE: usize = 200;
+    const AUTO_TAGS: usize = 3;
+
+    /// Content metrics; reading time assumes 200 words per minute, rounded up.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ContentStats {
+        pub chars: usize,
+        pub bytes: usize,
+        pub words: usize,
+        pub lines: usize,
+        pub reading_time_minutes: usize,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FeedItem {
+        pub title: String,
+        pub link: String,
+        pub content: String,
+        pub published_at: SystemTime,
+        pub author: String,
+        pub sticky: bool,
+    }
+
+    impl FeedItem {
+        /// A JSON object with `published_at` in whole seconds since the Unix
+        /// epoch.
+        pub fn to_json(&self) -> String {
+            let published_at = self
+                .published_at
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs());
+            format!(
+                "{{\"title\":{},\"link\":{},\"content\":{},\"published_at\":{},\"author\":{},\"sticky\":{}}}",
+                json_string(&self.title),
+                json_string(&self.link),
+                json_string(&self.content),
+                published_at,
+                json_string(&self.author),
+                self.sticky
+            )
+        }
+    }
+
+    fn json_string(text: &str) -> String {
+        let mut json = String::from("\"");
+        for c in text.chars() {
+            match c {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                '\n' => json.push_str("\\n"),
+                '\r' => json.push_str("\\r"),
+                '\t' => json.push_str("\\t"),
+                c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+                c => json.push(c),
+            }
+        }
+        json.push('"');
+        json
+    }
+
+    /// Sends a webhook request: `(url, json_body)`.
+    #[cfg(feature = "webhook")]
+    type WebhookSender = dyn Fn(&str, &str) -> io::Result<()>;
+
+    /// How long the built-in webhook sender waits to connect, write or read.
+    #[cfg(feature = "webhook")]
+    const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// A minimal blocking `POST` for plain `http://` URLs, giving up after
+    /// `WEBHOOK_TIMEOUT` at each step; anything fancier should be plugged in
+    /// with `with_webhook_sender`.
+    #[cfg(feature = "webhook")]
+    fn http_post(url: &str, body: &str) -> io::Result<()> {
+        use std::io::Write;
+        use std::net::{TcpStream, ToSocketAddrs};
+
+        let rest = url.strip_prefix("http://").ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "only http:// webhooks are supported",
+            )
+        })?;
+        let (host, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{}:80", host)
+        };
+        let mut stream = None;
+        let mut last_error =
+            io::Error::new(io::ErrorKind::NotFound, "webhook host did not resolve");
+        for addr in address.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, WEBHOOK_TIMEOUT) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(error) => last_error = error,
+            }
+        }
+        let mut stream = stream.ok_or(last_error)?;
+        stream.set_read_timeout(Some(WEBHOOK_TIMEOUT))?;
+        stream.set_write_timeout(Some(WEBHOOK_TIMEOUT))?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            path,
+            host,
+            body.len(),
+            body
+        )?;
+        let mut status = [0; 12];
+        stream.read_exact(&mut status)?;
+        if status.starts_with(b"HTTP/") && status[9] == b'2' {
+            Ok(())
+        } else {
+            Err(io::Error::other(
+                "the webhook did not answer with a 2xx status",
+            ))
+        }
+    }
+
+    pub enum RustWayPost {
+        Draft(rust_way::DraftPost),
+        PendingReview(rust_way::PendingReviewPost),
+        Approved(rust_way::ApprovedPendingReviewPost),
+        Published(rust_way::Post),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum PostError {
+        PublishVetoed,
+        NotPublished,
+        RevisionLimitReached,
+        CoolingDown,
+        InvalidExport,
+    }
+
+    impl fmt::Display for PostError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PostError::PublishVetoed => write!(f, "the publish guard vetoed publication"),
+                PostError::NotPublished => write!(f, "only published posts can be revised"),
+                PostError::RevisionLimitReached => write!(f, "the revision limit was reached"),
+                PostError::CoolingDown => write!(f, "the previous transition was too recent"),
+                PostError::InvalidExport => write!(f, "the input is not an export of posts"),
+            }
+        }
+    }
+
+    impl Error for PostError {}
+
+    /// Posts are ordered by `created_at`, oldest first, with ties broken by
+    /// comparing their content. Two posts are equal only when both match.
+    impl Ord for Post {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.created_at
+                .cmp(&other.created_at)
+                .then_with(|| self.content.cmp(&other.content))
+        }
+    }
+
+    impl PartialOrd for Post {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl PartialEq for Post {
+        fn eq(&self, other: &Self) -> bool {
+            self.cmp(other) == Ordering::Equal
+        }
+    }
+
+    impl Eq for Post {}
+
+    /// Collects Markdown link targets and bare `http(s)://` URLs, trimming
+    /// trailing punctuation from the latter.
+    fn extract_links(content: &str) -> Vec<&str> {
+        let mut links = Vec::new();
+        let mut position = 0;
+        while let Some(c) = content[position..].chars().next() {
+            let rest = &content[position..];
+            if let Some((target, _)) = rest.strip_prefix("](").and_then(|t| t.split_once(')')) {
+                if !target.trim().is_empty() {
+                    links.push(target.trim());
+                }
+                position += target.len() + 3;
+            } else if let Some(label) = rest
+                .strip_prefix('[')
+                .and_then(|label| label.find(']'))
+                .filter(|end| rest[end + 1..].starts_with("]("))
+            {
+                // A URL used as link text is the target again, so only the
+                // target after the label is kept.
+                position += label + 1;
+            } else if rest.starts_with("http://") || rest.starts_with("https://") {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "()<>[]\"'".contains(c))
+                    .unwrap_or(rest.len());
+                links.push(rest[..end].trim_end_matches(|c| ".,;:!?".contains(c)));
+                position += end;
+            } else {
+                position += c.len_utf8();
+            }
+        }
+        links
+    }
+
+    /// Curls straight quotes: a quote opens at the start of the text or after
+    /// whitespace or an opening bracket, and closes everywhere else, so
+    /// apostrophes inside words become `’`.
+    fn smart_quotes(text: &str) -> String {
+        let mut curled = String::with_capacity(text.len());
+        let mut previous: Option<char> = None;
+        for c in text.chars() {
+            let opens = previous.is_none_or(|p| p.is_whitespace() || "([{“‘".contains(p));
+            curled.push(match (c, opens) {
+                ('"', true) => '“',
+                ('"', false) => '”',
+                ('\'', true) => '‘',
+                ('\'', false) => '’',
+                _ => c,
+            });
+            previous = Some(c);
+        }
+        curled
+    }
+
+    fn render_html(markdown: &str, base: Option<&str>) -> String {
+        let lines: Vec<&str> = markdown.lines().collect();
+        let mut html = String::new();
+        let mut paragraph: Vec<&str> = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let alignments = lines.get(i + 1).and_then(|next| table_alignments(next));
+            if let Some(alignments) = alignments.filter(|_| line.contains('|')) {
+                flush_paragraph(&mut html, &mut paragraph, base);
+                i += render_table(&mut html, &lines[i..], &alignments, base);
+                continue;
+            }
+            if line.trim().is_empty() {
+                flush_paragraph(&mut html, &mut paragraph, base);
+            } else if let Some((level, text)) = parse_heading(line) {
+                flush_paragraph(&mut html, &mut paragraph, base);
+                html.push_str(&format!(
+                    "<h{0}>{1}</h{0}>\n",
+                    level,
+                    render_inline(text, base)
+                ));
+            } else {
+                paragraph.push(line.trim());
+            }
+            i += 1;
+        }
+        flush_paragraph(&mut html, &mut paragraph, base);
+        html
+    }
+
+    fn flush_paragraph(html: &mut String, paragraph: &mut Vec<&str>, base: Option<&str>) {
+        if !paragraph.is_empty() {
+            html.push_str(&format!(
+                "<p>{}</p>\n",
+                render_inline(&paragraph.join("\n"), base)
+            ));
+            paragraph.clear();
+        }
+    }
+
+    /// Escapes `text`, turning `[label](url)` into links and `![alt](url)`
+    /// into images.
+    fn render_inline(text: &str, base: Option<&str>) -> String {
+        let mut html = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('[') {
+            let image = rest[..start].ends_with('!');
+            let parsed = rest[start + 1..]
+                .split_once("](")
+                .and_then(|(label, after)| {
+                    let (url, after) = after.split_once(')')?;
+                    (!label.contains(['[', ']'])).then_some((label, url.trim(), after))
+                });
+            let Some((label, url, after)) = parsed else {
+                html.push_str(&escape_html(&rest[..=start]));
+                rest = &rest[start + 1..];
+                continue;
+            };
+            let before = if image {
+                &rest[..start - 1]
+            } else {
+                &rest[..start]
+            };
+            html.push_str(&escape_html(before));
+            let url = escape_html(&resolve_url(url, base));
+            let label = escape_html(label);
+            if image {
+                html.push_str(&format!("<img src=\"{}\" alt=\"{}\">", url, label));
+            } else {
+                html.push_str(&format!("<a href=\"{}\">{}</a>", url, label));
+            }
+            rest = after;
+        }
+        html.push_str(&escape_html(rest));
+        html
+    }
+
+    /// Whether `url` starts with a `scheme:` such as `https:`, `mailto:` or `tel:`.
+    fn has_scheme(url: &str) -> bool {
+        let Some((scheme, _)) = url.split_once(':') else {
+            return false;
+        };
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+    }
+
+    fn resolve_url(url: &str, base: Option<&str>) -> String {
+        let absolute = has_scheme(url) || url.starts_with("//") || url.starts_with('#');
+        match base {
+            Some(base) if !absolute => {
+                format!(
+                    "{}/{}",
+                    base.trim_end_matches('/'),
+                    url.trim_start_matches('/')
+                )
+            }
+            _ => url.to_string(),
+        }
+    }
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn split_row(line: &str) -> Vec<&str> {
+        let line = line.trim();
+        let line = line.strip_prefix('|').unwrap_or(line);
+        let line = line.strip_suffix('|').unwrap_or(line);
+        line.split('|').map(str::trim).collect()
+    }
+
+    /// Parses a separator row such as `| :--- | ---: |` into each column's
+    /// alignment, or `None` if the line is not a separator row.
+    fn table_alignments(line: &str) -> Option<Vec<Option<&'static str>>> {
+        if !line.contains('|') {
+            return None;
+        }
+        split_row(line)
+            .into_iter()
+            .map(|cell| {
+                let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+                if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                    return None;
+                }
+                Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                    (true, true) => Some("center"),
+                    (false, true) => Some("right"),
+                    (true, false) => Some("left"),
+                    (false, false) => None,
+                })
+            })
+            .collect()
+    }
+
+    /// Renders the table starting at `lines[0]` and returns how many lines it
+    /// spanned: the header, the separator and every following row.
+    fn render_table(
+        html: &mut String,
+        lines: &[&str],
+        alignments: &[Option<&str>],
+        base: Option<&str>,
+    ) -> usize {
+        let row = |html: &mut String, line: &str, tag: &str| {
+            let cells = split_row(line);
+            html.push_str("<tr>");
+            for (i, alignment) in alignments.iter().enumerate() {
+                let cell = render_inline(cells.get(i).copied().unwrap_or(""), base);
+                match alignment {
+                    Some(align) => {
+                        html.push_str(&format!("<{0} align=\"{1}\">{2}</{0}>", tag, align, cell))
+                    }
+                    None => html.push_str(&format!("<{0}>{1}</{0}>", tag, cell)),
+                }
+            }
+            html.push_str("</tr>\n");
+        };
+        html.push_str("<table>\n<thead>\n");
+        row(html, lines[0], "th");
+        html.push_str("</thead>\n<tbody>\n");
+        let mut spanned = 2;
+        for line in &lines[2..] {
+            if line.trim().is_empty() || !line.contains('|') {
+                break;
+            }
+            row(html, line, "td");
+            spanned += 1;
+        }
+        html.push_str("</tbody>\n</table>\n");
+        spanned
+    }
+
+    /// Parses an ATX Markdown heading such as `## Title` into its level and text.
+    fn parse_heading(line: &str) -> Option<(u8, &str)> {
+        let line = line.trim_start();
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let rest = &line[level..];
+        if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            return None;
+        }
+        Some((level as u8, rest.trim()))
+    }
+
+    /// Rewrites each heading line with `f`, leaving every other line untouched.
+    /// Headings in `text`, skipping lines inside ``` fenced code blocks.
+    fn parse_headings(text: &str) -> Vec<(u8, &str)> {
+        let mut fenced = false;
+        text.lines()
+            .filter_map(|line| {
+                if line.trim_start().starts_with("```") {
+                    fenced = !fenced;
+                    return None;
+                }
+                if fenced {
+                    None
+                } else {
+                    parse_heading(line)
+                }
+            })
+            .collect()
+    }
+    /// Rewrites each heading with `f`, leaving fenced code blocks untouched.
+    fn map_headings(text: &str, f: impl Fn(u8, &str) -> String) -> String {
+        let mut fenced = false;
+        text.split_inclusive('\n')
+            .map(|line| {
+                let body = line.trim_end_matches(['\r', '\n']);
+                if body.trim_start().starts_with("```") {
+                    fenced = !fenced;
+                    return line.to_string();
+                }
+                match parse_heading(body).filter(|_| !fenced) {
+                    Some((level, heading)) => {
+                        format!("{}{}", f(level, heading), &line[body.len()..])
+                    }
+                    None => line.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    fn title_case(text: &str) -> String {
+        text.split(' ')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                        .collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn split_sentences(text: &str) -> Vec<&str> {
+        let mut sentences = Vec::new();
+        let mut start = 0;
+        let mut chars = text.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let at_end = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if matches!(c, '.' | '!' | '?') && at_end {
+                sentences.push(text[start..i + 1].trim());
+                start = i + 1;
+            }
+        }
+        sentences.push(text[start..].trim());
+        sentences.retain(|sentence| !sentence.is_empty());
+        sentences
+    }
+
+    /// Approximates emoji by the pictograph, symbol and dingbat blocks plus the
+    /// joiners and selectors used to compose them; some symbols outside these
+    /// blocks are missed and a few non-emoji symbols are caught.
+    fn is_emoji(c: char) -> bool {
+        matches!(c as u32,
+            0x1F000..=0x1FAFF
+            | 0x2600..=0x27BF
+            | 0x2B00..=0x2BFF
+            | 0x200D
+            | 0xFE0E..=0xFE0F
+            | 0xE0020..=0xE007F)
+    }
+
+    const STOPWORDS: &[&str] = &[
+        "a", "about", "after", "all", "an", "and", "are", "as", "at", "be", "but", "by", "can",
+        "for", "from", "had", "has", "have", "he", "her", "his", "i", "if", "in", "into", "is",
+        "it", "its", "me", "my", "no", "not", "of", "on", "or", "our", "she", "so", "that", "the",
+        "their", "them", "then", "there", "they", "this", "to", "too", "up", "was", "we", "were",
+        "what", "when", "which", "who", "will", "with", "you", "your",
+    ];
+
+    /// Lowercased words with surrounding punctuation removed.
+    fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+        text.split_whitespace()
+            .map(|word| {
+                word.trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|word| !word.is_empty())
+    }
+
+    fn count_syllables(word: &str) -> usize {
+        let is_vowel = |c: char| "aeiouy".contains(c);
+        let mut syllables = 0;
+        let mut previous_was_vowel = false;
+        for c in word.chars() {
+            let vowel = is_vowel(c);
+            if vowel && !previous_was_vowel {
+                syllables += 1;
+            }
+            previous_was_vowel = vowel;
+        }
+        if word.ends_with('e') && !word.ends_with("le") && syllables > 1 {
+            syllables -= 1;
+        }
+        syllables.max(1)
+    }
+
+    /// Lowercase ASCII letters and digits, one `-` between words; every
+    /// other character is dropped so the slug is URL-safe as is.
+    fn slugify(text: &str) -> String {
+        text.to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '-')
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>()
+            })
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Replaces every case-insensitive, whole-word occurrence of `word` in `text`.
+    fn replace_words(text: &str, word: &str, replace: impl Fn(&str) -> String) -> String {
+        let mut replaced = String::new();
+        let mut copied = 0;
+        let mut position = 0;
+        while let Some(c) = text[position..].chars().next() {
+            if let Some(end) = match_word_at(text, position, word) {
+                replaced.push_str(&text[copied..position]);
+                replaced.push_str(&replace(&text[position..end]));
+                copied = end;
+                position = end;
+            } else {
+                position += c.len_utf8();
+            }
+        }
+        replaced.push_str(&text[copied..]);
+        replaced
+    }
+
+    fn contains_word(text: &str, word: &str) -> bool {
+        text.char_indices()
+            .any(|(start, _)| match_word_at(text, start, word).is_some())
+    }
+
+    /// Returns the end of `word` if it appears as a whole word at `start`.
+    fn match_word_at(text: &str, start: usize, word: &str) -> Option<usize> {
+        if word.is_empty()
+            || text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+        {
+            return None;
+        }
+        let mut end = start;
+        let mut chars = text[start..].chars();
+        for expected in word.chars() {
+            let c = chars.next()?;
+            if !c.to_lowercase().eq(expected.to_lowercase()) {
+                return None;
+            }
+            end += c.len_utf8();
+        }
+        if chars.next().is_some_and(char::is_alphanumeric) {
+            return None;
+        }
+        Some(end)
+    }
+
+    const BASE64_ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    /// XORs every byte of `content` with `key` and base64-encodes the result.
+    /// This only hides embargoed content from casual readers; it is not encryption.
+    fn scramble(content: &str, key: u8) -> String {
+        let bytes: Vec<u8> = content.bytes().map(|byte| byte ^ key).collect();
+        let mut encoded = String::new();
+        for chunk in bytes.chunks(3) {
+            let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+                group | (*byte as u32) << (16 - 8 * i)
+            });
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    encoded.push(BASE64_ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+                } else {
+                    encoded.push('=');
+                }
+            }
+        }
+        encoded
+    }
+
+    pub fn unscramble(scrambled: &str, key: u8) -> Option<String> {
+        let mut bytes = Vec::new();
+        for chunk in scrambled.trim_end_matches('=').as_bytes().chunks(4) {
+            let mut group = 0u32;
+            for (i, symbol) in chunk.iter().enumerate() {
+                let value = BASE64_ALPHABET.iter().position(|c| c == symbol)?;
+                group |= (value as u32) << (18 - 6 * i);
+            }
+            for i in 0..chunk.len().saturating_sub(1) {
+                bytes.push((group >> (16 - 8 * i)) as u8 ^ key);
+            }
+        }
+        String::from_utf8(bytes).ok()
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum PostState {
+        Draft,
+        PendingReview,
+        Published,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum PostType {
+        #[default]
+        Article,
+        Note,
+        Announcement,
+    }
+
+    impl PostType {
+        /// Notes are low-stakes and publish after a single approval.
+        pub fn required_approvals(self) -> u8 {
+            match self {
+                PostType::Article | PostType::Announcement => 2,
+                PostType::Note => 1,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Action {
+        RequestReview,
+        Approve,
+        Reject,
+        CancelReview,
+    }
+
+    /// An accepted action: either the state changed or a review was counted.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Transition {
+        pub action: Action,
+        pub from: PostState,
+        pub to: PostState,
+        pub at: SystemTime,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum DiffOp {
+        Equal(String),
+        Insert(String),
+        Delete(String),
+    }
+
+    /// A line-based diff built from the longest common subsequence.
+    fn diff_lines(old: &str, new: &str) -> Vec<DiffOp> {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+        let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        let mut ops = Vec::new();
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                ops.push(DiffOp::Equal(old[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push(DiffOp::Delete(old[i].to_string()));
+                i += 1;
+            } else {
+                ops.push(DiffOp::Insert(new[j].to_string()));
+                j += 1;
+            }
+        }
+        ops
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum PostEvent {
+        ReviewRequested { role: String },
+        TextAdded(String),
+    }
+
+    pub trait Subscriber {
+        fn notify(&self, event: &PostEvent);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Command {
+        AddText(String),
+        ClearText,
+        RequestReview,
+        Approve,
+        ApproveWithComment(String),
+        ApproveBy(String),
+        ApproveByRole(String, String),
+        Reject,
+        RejectBy(String),
+        CancelReview,
+        Revise(String),
+        RevertToRevision(u32),
+    }
+
+    trait State {
+        fn state(&self) -> PostState;
+        fn boxed_clone(&self) -> Box<dyn State>;
+        fn request_review(self: Box<Self>) -> Box<dyn State>;
+        fn approve(self: Box<Self>, post: &Post) -> Box<dyn State>;
+        fn reject(self: Box<Self>, post: &Post) -> Box<dyn State>;
+        fn cancel_review(self: Box<Self>) -> Box<dyn State>;
+        fn content<'a>(&self, _post: &'a Post) -> &'a str {
+            ""
+        }
+        fn rejections(&self) -> u8 {
+            0
+        }
+        fn record_role_approval(&self, _reviewer: &str, _role: &str) {}
+        fn record_approver(&self, _reviewer: &str) {}
+        fn has_approved(&self, _reviewer: &str) -> bool {
+            false
+        }
+        fn approvers(&self) -> Vec<String> {
+            Vec::new()
+        }
+        fn record_rejecter(&self, _reviewer: &str) {}
+        fn has_rejected(&self, _reviewer: &str) -> bool {
+            false
+        }
+        fn outstanding_roles<'a>(&self, post: &'a Post) -> Vec<&'a str> {
+            post.required_roles.iter().map(String::as_str).collect()
+        }
+        fn is_pending(&self) -> bool {
+            false
+        }
+        fn clear_text(&self, current_content: &str) -> String {
+            current_content.to_string()
+        }
+        fn approvals(&self) -> u8 {
+            0
+        }
+        fn is_editable(&self) -> bool {
+            false
+        }
+        fn is_terminal(&self) -> bool {
+            false
+        }
+        fn available_actions(&self) -> Vec<Action> {
+            Vec::new()
+        }
+        fn rejecters(&self) -> Vec<String> {
+            Vec::new()
+        }
+        fn role_approvals(&self) -> Vec<(String, String)> {
+            Vec::new()
+        }
+        fn add_text(&self, current_content: &str, _text_to_append: &str) -> String {
+            current_content.to_string()
+        }
+    }
+
+    #[derive(Clone)]
+    struct Draft {}
+
+    impl State for Draft {
+        fn state(&self) -> PostState {
+            PostState::Draft
+        }
+        fn boxed_clone(&self) -> Box<dyn State> {
+            Box::new(self.clone())
+        }
+        fn request_review(self: Box<Self>) -> Box<dyn State> {
+            Box::new(PendingReview::new())
+        }
+        fn approve(self: Box<Self>, _post: &Post) -> Box<dyn State> {
+            self
+        }
+        fn reject(self: Box<Self>, _post: &Post) -> Box<dyn State> {
+            self
+        }
+        fn cancel_review(self: Box<Self>) -> Box<dyn State> {
+            self
+        }
+        fn add_text(&self, current_content: &str, text_to_append: &str) -> String {
+            format!("{}{}", current_content, text_to_append)
+        }
+        fn clear_text(&self, _current_content: &str) -> String {
+            String::new()
+        }
+        fn is_editable(&self) -> bool {
+            true
+        }
+        fn available_actions(&self) -> Vec<Action> {
+            vec![Action::RequestReview]
+        }
+    }
+
+    #[derive(Clone)]
+    struct PendingReview {
+        approvals: RefCell<u8>,
+        rejections: RefCell<u8>,
+        role_approvals: RefCell<Vec<(String, String)>>,
+        approvers: RefCell<Vec<String>>,
+        rejecters: RefCell<Vec<String>>,
+    }
+
+    impl PendingReview {
+        fn new() -> PendingReview {
+            PendingReview {
+                approvals: RefCell::new(0),
+                rejections: RefCell::new(0),
+                role_approvals: RefCell::new(Vec::new()),
+                approvers: RefCell::new(Vec::new()),
+                rejecters: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl State for PendingReview {
+        fn state(&self) -> PostState {
+            PostState::PendingReview
+        }
+        fn boxed_clone(&self) -> Box<dyn State> {
+            Box::new(self.clone())
+        }
+        fn request_review(self: Box<Self>) -> Box<dyn State> {
+            self
+        }
+        fn approve(self: Box<Self>, post: &Post) -> Box<dyn State> {
+            let approvals = self.approvals.borrow().saturating_add(1);
+            *self.approvals.borrow_mut() = approvals;
+            if post.approvals_met(&*self) && self.outstanding_roles(post).is_empty() {
+                Box::new(Published {})
+            } else {
+                self
+            }
+        }
+        fn reject(self: Box<Self>, post: &Post) -> Box<dyn State> {
+            let rejections = self.rejections.borrow().saturating_add(1);
+            *self.rejections.borrow_mut() = rejections;
+            if *self.rejections.borrow() >= post.required_rejections {
+                Box::new(Draft {})
+            } else {
+                self
+            }
+        }
+        fn cancel_review(self: Box<Self>) -> Box<dyn State> {
+            Box::new(Draft {})
+        }
+        fn available_actions(&self) -> Vec<Action> {
+            vec![Action::Approve, Action::Reject, Action::CancelReview]
+        }
+        fn approvals(&self) -> u8 {
+            *self.approvals.borrow()
+        }
+        fn rejections(&self) -> u8 {
+            *self.rejections.borrow()
+        }
+        fn is_pending(&self) -> bool {
+            true
+        }
+        fn record_role_approval(&self, reviewer: &str, role: &str) {
+            self.role_approvals
+                .borrow_mut()
+                .push((reviewer.to_string(), role.to_string()));
+        }
+        fn record_approver(&self, reviewer: &str) {
+            self.approvers.borrow_mut().push(reviewer.to_string());
+        }
+        fn has_approved(&self, reviewer: &str) -> bool {
+            self.approvers
+                .borrow()
+                .iter()
+                .any(|approver| approver == reviewer)
+        }
+        fn approvers(&self) -> Vec<String> {
+            self.approvers.borrow().clone()
+        }
+        fn record_rejecter(&self, reviewer: &str) {
+            self.rejecters.borrow_mut().push(reviewer.to_string());
+        }
+        fn has_rejected(&self, reviewer: &str) -> bool {
+            self.rejecters
+                .borrow()
+                .iter()
+                .any(|rejecter| rejecter == reviewer)
+        }
+        fn rejecters(&self) -> Vec<String> {
+            self.rejecters.borrow().clone()
+        }
+        fn role_approvals(&self) -> Vec<(String, String)> {
+            self.role_approvals.borrow().clone()
+        }
+        fn outstanding_roles<'a>(&self, post: &'a Post) -> Vec<&'a str> {
+            let role_approvals = self.role_approvals.borrow();
+            post.required_roles
+                .iter()
+                .filter(|required| !role_approvals.iter().any(|(_, role)| role == *required))
+                .map(String::as_str)
+                .collect()
+        }
+    }
+
+    #[derive(Clone)]
+    struct Published {}
+
+    impl State for Published {
+        fn state(&self) -> PostState {
+            PostState::Published
+        }
+        fn boxed_clone(&self) -> Box<dyn State> {
+            Box::new(self.clone())
+        }
+        fn request_review(self: Box<Self>) -> Box<dyn State> {
+            self
+        }
+        fn approve(self: Box<Self>, _post: &Post) -> Box<dyn State> {
+            self
+        }
+        fn content<'a>(&self, post: &'a Post) -> &'a str {
+            &post.content
+        }
+        fn outstanding_roles<'a>(&self, _post: &'a Post) -> Vec<&'a str> {
+            Vec::new()
+        }
+        fn is_terminal(&self) -> bool {
+            true
+        }
+        fn reject(self: Box<Self>, _post: &Post) -> Box<dyn State> {
+            self
+        }
+        fn cancel_review(self: Box<Self>) -> Box<dyn State> {
+            self
+        }
+    }
+}
+
+pub mod rust_way {
+    use std::ops::Deref;
+
+    /// Content that a draft can grow one piece of text at a time.
+    pub trait Extendable {
+        fn extend_text(&mut self, text: &str);
+        fn is_empty(&self) -> bool;
+    }
+
+    impl Extendable for String {
+        fn extend_text(&mut self, text: &str) {
+            self.push_str(text);
+        }
+
+        fn is_empty(&self) -> bool {
+            String::is_empty(self)
+        }
+    }
+
+    /// Keeps every piece of added text as its own entry.
+    impl Extendable for Vec<String> {
+        fn extend_text(&mut self, text: &str) {
+            self.push(text.to_string());
+        }
+
+        fn is_empty(&self) -> bool {
+            Vec::is_empty(self)
+        }
+    }
+
+    pub struct Post<C = String> {
+        pub(crate) content: C,
+    }
+
+    impl Post {
+        #[allow(clippy::new_ret_no_self)]
+        pub fn new() -> DraftPost {
+            DraftPost::new()
+        }
+    }
+
+    impl<C: Deref> Post<C> {
+        pub fn content(&self) -> &C::Target {
+            &self.content
+        }
+    }
+
+    impl<C> Post<C> {
+        pub fn archive(self) -> ArchivedPost<C> {
+            ArchivedPost {
+                content: self.content,
+            }
+        }
+    }
+
+    /// An archived post is read-only and final: there is intentionally no way
+    /// back to `Post`, so archived content can never be republished or edited.
+    pub struct ArchivedPost<C = String> {
+        content: C,
+    }
+
+    impl<C: Deref> ArchivedPost<C> {
+        pub fn content(&self) -> &C::Target {
+            &self.content
+        }
+    }
+    pub struct DraftPost<C = String> {
+        pub(crate) content: C,
+    }
+
+    impl<C: Default + Extendable> DraftPost<C> {
+        /// Starts a draft over any content type; `Post::new` is the `String` case.
+        pub fn new() -> DraftPost<C> {
+            DraftPost {
+                content: C::default(),
+            }
+        }
+
+        pub fn add_text(&mut self, text: &str) {
+            self.content.extend_text(text);
+        }
+
+        pub fn request_review(self) -> PendingReviewPost<C> {
+            PendingReviewPost {
+                content: self.content,
+            }
+        }
+
+        pub fn try_request_review(self) -> Result<PendingReviewPost<C>, DraftPost<C>> {
+            if self.content.is_empty() {
+                Err(self)
+            } else {
+                Ok(self.request_review())
+            }
+        }
+    }
+
+    impl<C: Default + Extendable> Default for DraftPost<C> {
+        fn default() -> Self {
+            DraftPost::new()
+        }
+    }
+
+    impl<C: Deref> DraftPost<C> {
+        pub fn content(&self) -> &C::Target {
+            &self.content
+        }
+    }
+    pub struct PendingReviewPost<C = String> {
+        pub(crate) content: C,
+    }
+
+    impl<C> PendingReviewPost<C> {
+        pub fn approvals_needed() -> u8 {
+            2
+        }
+    }
+
+    impl<C: Default + Extendable> PendingReviewPost<C> {
+        pub fn approve(self) -> ApprovedPendingReviewPost<C> {
+            ApprovedPendingReviewPost {
+                content: self.content,
+            }
+        }
+
+        pub fn reject(self) -> DraftPost<C> {
+            DraftPost {
+                content: self.content,
+            }
+        }
+
+        pub fn reject_and_clear(self) -> DraftPost<C> {
+            DraftPost::new()
+        }
+    }
+
+    pub struct ApprovedPendingReviewPost<C = String> {
+        pub(crate) content: C,
+    }
+
+    impl<C> ApprovedPendingReviewPost<C> {
+        pub fn approvals_needed() -> u8 {
+            1
+        }
+    }
+
+    impl<C: Default + Extendable> ApprovedPendingReviewPost<C> {
+        pub fn approve(self) -> Post<C> {
+            Post {
+                content: self.content,
+            }
+        }
+
+        pub fn reject(self) -> DraftPost<C> {
+            DraftPost {
+                content: self.content,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod oop {
+        use crate::oop::testing::assert_invariants;
+        use crate::oop::{
+            bulk_approve, bulk_request_review, export_all, import_all, import_markdown,
+            purge_expired, sort_feed, unscramble, Action, Command, ContentStats, DiffOp, FeedItem,
+            FixedClock, Post, PostError, PostEvent, PostState, PostType, RustWayPost, Subscriber,
+            Transition,
+        };
+        use std::cell::{Cell, RefCell};
+        use std::collections::{HashMap, HashSet};
+        use std::rc::Rc;
+        use std::time::{Duration, SystemTime};
+
+        #[derive(Default)]
+        struct Inbox {
+            events: RefCell<Vec<PostEvent>>,
+        }
+
+        impl Subscriber for Inbox {
+            fn notify(&self, event: &PostEvent) {
+                self.events.borrow_mut().push(event.clone());
+            }
+        }
+
+        #[test]
+        fn it_adds_text_only_in_draft() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!("", post.content());
+
+            post.request_review();
+            assert_eq!("", post.content());
+
+            post.add_text("and a steak for dinner");
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_can_reject_posts() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!("", post.content());
+
+            post.request_review();
+            assert_eq!("", post.content());
+
+            post.reject();
+            assert_eq!("", post.content());
+
+            post.add_text(" and a steak for dinner");
+            assert_eq!("", post.content());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_requires_two_approvals() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!("", post.content());
+
+            post.request_review();
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!("", post.content());
+        }
+
+        #[test]
+        fn it_prints_content_when_published() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!("", post.content());
+
+            post.request_review();
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_counts_lines_in_any_state() {
+            let mut post = Post::new();
+            assert_eq!(0, post.line_count());
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!(1, post.line_count());
+
+            post.add_text("\n");
+            assert_eq!(1, post.line_count());
+
+            post.add_text("and a steak for dinner\nand cake for dessert");
+            assert_eq!(3, post.line_count());
+
+            post.request_review();
+            assert_eq!(3, post.line_count());
+        }
+
+        #[test]
+        fn it_requires_two_rejections_when_configured() {
+            let mut post = Post::new().with_required_rejections(2);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(0, post.rejections());
+
+            post.reject();
+            assert_eq!(1, post.rejections());
+
+            post.add_text(" and a steak for dinner");
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+
+            let mut post = Post::new().with_required_rejections(2);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.reject();
+            post.reject();
+            assert_eq!(0, post.rejections());
+
+            post.add_text(" and a steak for dinner");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_renders_placeholders_when_published() {
+            let mut post = Post::new();
+            let mut vars = HashMap::new();
+            vars.insert("date", String::from("Monday"));
+
+            post.add_text("On {{date}} I ate a salad for {{meal}}");
+            assert_eq!("", post.render_with(&vars));
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "On Monday I ate a salad for {{meal}}",
+                post.render_with(&vars)
+            );
+
+            vars.insert("meal", String::from("lunch"));
+            assert_eq!("On Monday I ate a salad for lunch", post.render_with(&vars));
+        }
+
+        #[test]
+        fn it_clones_a_published_post_as_an_editable_draft() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            let mut draft = post.clone_as_draft();
+            assert_eq!("", draft.content());
+
+            draft.add_text(" and a steak for dinner");
+            draft.request_review();
+            draft.approve();
+            assert_eq!("", draft.content());
+
+            draft.approve();
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                draft.content()
+            );
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_measures_bytes_in_any_state() {
+            let mut post = Post::new();
+            assert_eq!(0, post.byte_len());
+
+            post.add_text("Comí una ensalada 🥗");
+            assert_eq!(23, post.byte_len());
+            assert_ne!(post.byte_len(), "Comí una ensalada 🥗".chars().count());
+
+            post.request_review();
+            assert_eq!(23, post.byte_len());
+        }
+
+        #[test]
+        fn it_requires_an_approval_from_each_role() {
+            let mut post = Post::new().with_required_roles(&["editor", "legal"]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(vec!["editor", "legal"], post.outstanding_roles());
+
+            post.approve_by_role("alice", "editor");
+            post.approve_by_role("bob", "editor");
+            assert_eq!(vec!["legal"], post.outstanding_roles());
+            assert_eq!("", post.content());
+
+            post.approve_by_role("carol", "legal");
+            assert!(post.outstanding_roles().is_empty());
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_publishes_once_both_roles_have_approved() {
+            let mut post = Post::new().with_required_roles(&["editor", "legal"]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+
+            post.approve_by_role("alice", "editor");
+            assert_eq!("", post.content());
+
+            post.approve_by_role("carol", "legal");
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_ignores_role_approvals_from_reviewers_outside_the_allowlist() {
+            let mut post = Post::new()
+                .with_required_roles(&["legal"])
+                .with_reviewer_allowlist(&["alice", "bob"]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by_role("mallory", "legal");
+            assert_eq!(vec!["legal"], post.outstanding_roles());
+
+            post.approve_by("alice");
+            post.approve_by("bob");
+            assert_eq!(PostState::PendingReview, post.state());
+        }
+
+        #[test]
+        fn it_ignores_a_second_role_claimed_by_the_same_reviewer() {
+            let mut post = Post::new().with_required_roles(&["editor", "legal"]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by_role("alice", "editor");
+            post.approve_by_role("alice", "legal");
+            assert_eq!(vec!["legal"], post.outstanding_roles());
+
+            post.approve_by("bob");
+            assert_eq!(PostState::PendingReview, post.state());
+            post.approve_by_role("carol", "legal");
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_sorts_posts_by_creation_time() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+
+            clock.advance(Duration::from_secs(20));
+            let mut newest = Post::new().with_clock(clock.clone());
+            newest.add_text("newest");
+
+            clock.set(SystemTime::UNIX_EPOCH);
+            let mut oldest = Post::new().with_clock(clock.clone());
+            oldest.add_text("oldest");
+
+            clock.advance(Duration::from_secs(10));
+            let mut middle_b = Post::new().with_clock(clock.clone());
+            middle_b.add_text("bb");
+            let mut middle_a = Post::new().with_clock(clock.clone());
+            middle_a.add_text("a");
+
+            let mut posts = Vec::from([newest, middle_b, oldest, middle_a]);
+            posts.sort();
+
+            let order: Vec<usize> = posts.iter().map(|post| post.byte_len()).collect();
+            assert_eq!(vec![6, 1, 2, 6], order);
+            assert_eq!(SystemTime::UNIX_EPOCH, posts[0].created_at());
+            assert_eq!(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(20),
+                posts[3].created_at()
+            );
+        }
+
+        #[test]
+        fn it_scrambles_content_while_embargoed() {
+            let mut post = Post::new().with_embargo(42);
+
+            post.add_text("I ate a salad for lunch today 🥗");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            let scrambled = post.content().into_owned();
+            assert_ne!("I ate a salad for lunch today 🥗", scrambled);
+            assert!(scrambled.is_ascii());
+            assert_eq!(
+                Some(String::from("I ate a salad for lunch today 🥗")),
+                unscramble(&scrambled, 42)
+            );
+
+            post.lift_embargo();
+            assert_eq!("I ate a salad for lunch today 🥗", post.content());
+        }
+
+        #[test]
+        fn it_round_trips_scrambled_content_of_any_length() {
+            for text in ["a", "ab", "abc", "abcd"] {
+                let mut post = Post::new().with_embargo(7);
+                post.add_text(text);
+                post.request_review();
+                post.approve();
+                post.approve();
+
+                assert_eq!(Some(String::from(text)), unscramble(&post.content(), 7));
+            }
+        }
+
+        #[test]
+        fn it_retries_review_from_draft() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(post.retry_review());
+            assert!(!post.retry_review());
+
+            post.reject();
+            assert!(post.retry_review());
+
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_fails_to_retry_review_when_published() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert!(!post.retry_review());
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_separates_paragraphs_with_a_blank_line() {
+            let mut post = Post::new();
+
+            post.add_paragraph("I ate a salad for lunch today");
+            post.add_paragraph("I had a steak for dinner");
+            post.request_review();
+            post.add_paragraph("And cake for dessert");
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "I ate a salad for lunch today\n\nI had a steak for dinner",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_tracks_when_the_current_state_was_entered() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+            assert_eq!(None, post.state_since());
+
+            post.add_text("I ate a salad for lunch today");
+            clock.advance(Duration::from_secs(60));
+            post.request_review();
+            let pending_since = SystemTime::UNIX_EPOCH + Duration::from_secs(60);
+            assert_eq!(Some(pending_since), post.state_since());
+
+            clock.advance(Duration::from_secs(60));
+            post.approve();
+            assert_eq!(Some(pending_since), post.state_since());
+
+            clock.advance(Duration::from_secs(60));
+            post.approve();
+            assert_eq!(
+                Some(SystemTime::UNIX_EPOCH + Duration::from_secs(180)),
+                post.state_since()
+            );
+        }
+
+        #[test]
+        fn it_applies_actions_in_bulk() {
+            let mut draft = Post::new();
+            draft.add_text("draft");
+
+            let mut pending = Post::new();
+            pending.add_text("pending");
+            pending.request_review();
+
+            let mut approved_once = Post::new();
+            approved_once.add_text("approved once");
+            approved_once.request_review();
+            approved_once.approve();
+
+            let mut posts = [draft, pending, approved_once];
+            assert_eq!(1, bulk_request_review(&mut posts));
+            assert_eq!(1, bulk_approve(&mut posts));
+            assert_eq!("approved once", posts[2].content());
+
+            assert_eq!(2, bulk_approve(&mut posts));
+            assert_eq!("draft", posts[0].content());
+            assert_eq!("pending", posts[1].content());
+        }
+
+        #[test]
+        fn it_allows_review_just_under_the_word_cap() {
+            let mut post = Post::new().with_max_review_words(6);
+
+            post.add_text("I ate a salad for lunch");
+            assert!(!post.exceeds_review_word_limit());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad for lunch", post.content());
+        }
+
+        #[test]
+        fn it_blocks_review_over_the_word_cap() {
+            let mut post = Post::new().with_max_review_words(6);
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(post.exceeds_review_word_limit());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("", post.content());
+
+            let mut post = post.clone_as_draft().with_max_review_words(7);
+            assert!(!post.exceeds_review_word_limit());
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_verifies_a_stored_checksum() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            let checksum = post.checksum();
+            assert!(post.verify_checksum(checksum));
+
+            let mut copy = post.clone_as_draft();
+            assert!(copy.verify_checksum(checksum));
+
+            copy.add_text(" and a steak for dinner");
+            assert!(!copy.verify_checksum(checksum));
+        }
+
+        #[test]
+        fn it_previews_transitions_without_applying_them() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad for lunch today");
+
+            let actions = [
+                Action::Approve,
+                Action::RequestReview,
+                Action::RequestReview,
+                Action::Approve,
+                Action::Reject,
+                Action::RequestReview,
+                Action::Approve,
+                Action::Approve,
+                Action::Reject,
+            ];
+            for action in actions {
+                let preview = post.preview_transition(action);
+                assert_eq!(preview, post.preview_transition(action));
+                match action {
+                    Action::RequestReview => post.request_review(),
+                    Action::Approve => post.approve(),
+                    Action::Reject => post.reject(),
+                    Action::CancelReview => post.cancel_review(),
+                }
+                assert_eq!(preview, post.state());
+            }
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_previews_a_blocked_review_as_staying_in_draft() {
+            let mut post = Post::new().with_max_review_words(1);
+            post.add_text("I ate a salad for lunch today");
+
+            assert_eq!(
+                PostState::Draft,
+                post.preview_transition(Action::RequestReview)
+            );
+        }
+
+        #[test]
+        fn it_wraps_published_content_at_word_boundaries() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!("", post.wrapped(10));
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a\nsalad for\nlunch\ntoday", post.wrapped(10));
+        }
+
+        #[test]
+        fn it_breaks_overlong_words_when_wrapping() {
+            let mut post = Post::new();
+
+            post.add_text("I ate supercalifragilistic salad");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate\nsupercal\nifragili\nstic\nsalad", post.wrapped(8));
+        }
+
+        #[test]
+        fn it_keeps_reviewer_comments_after_publishing() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.approve_with_comment("Not ready yet");
+            post.request_review();
+            post.approve_with_comment("LGTM, fix typo in para 2");
+            post.approve_with_comment("Ship it");
+            post.approve_with_comment("Already published");
+
+            assert_eq!("I ate a salad for lunch today", post.content());
+            assert_eq!(["LGTM, fix typo in para 2", "Ship it"], post.comments());
+        }
+
+        #[test]
+        fn it_tells_a_pristine_draft_from_a_cleared_one() {
+            let mut post = Post::new();
+            assert!(!post.was_edited());
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(post.was_edited());
+
+            post.clear_text();
+            assert_eq!(0, post.byte_len());
+            assert!(post.was_edited());
+            assert!(!Post::new().was_edited());
+        }
+
+        #[test]
+        fn it_only_clears_text_in_draft() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.clear_text();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_converts_a_draft_into_rust_way() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad for lunch today");
+
+            let mut draft = match post.into_rust_way() {
+                RustWayPost::Draft(draft) => draft,
+                _ => panic!("expected a draft"),
+            };
+            draft.add_text(" and a steak for dinner");
+            let post = draft.request_review().approve().approve();
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_converts_a_pending_post_into_rust_way() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+
+            let pending = match post.into_rust_way() {
+                RustWayPost::PendingReview(pending) => pending,
+                _ => panic!("expected a pending review"),
+            };
+            let post = pending.approve().approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_converts_an_approved_post_into_rust_way() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+
+            let approved = match post.into_rust_way() {
+                RustWayPost::Approved(approved) => approved,
+                _ => panic!("expected an approved pending review"),
+            };
+            let post = approved.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_converts_a_pending_post_by_the_approvals_it_still_needs() {
+            let mut note = Post::new().with_type(PostType::Note);
+            note.add_text("I ate a salad for lunch today");
+            note.request_review();
+            assert!(matches!(note.into_rust_way(), RustWayPost::Approved(_)));
+
+            let mut post = Post::new().with_required_roles(&["legal", "editor"]);
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert!(matches!(
+                post.into_rust_way(),
+                RustWayPost::PendingReview(_)
+            ));
+        }
+
+        #[test]
+        fn it_converts_a_published_post_into_rust_way() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            match post.into_rust_way() {
+                RustWayPost::Published(post) => {
+                    assert_eq!("I ate a salad for lunch today", post.content())
+                }
+                _ => panic!("expected a published post"),
+            }
+        }
+
+        #[test]
+        fn it_redacts_a_word_from_visible_content() {
+            let mut post = Post::new().with_redactions(&["alice"]);
+
+            post.add_text("Alice ate a salad with malice, then alice left");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "█████ ate a salad with malice, then █████ left",
+                post.content()
+            );
+            assert_eq!(46, post.byte_len());
+        }
+
+        #[test]
+        fn it_redacts_multiple_words_from_visible_content() {
+            let mut post = Post::new().with_redactions(&["Alice", "hunter2"]);
+
+            post.add_text("alice's password is HUNTER2.");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!("█████'s password is ███████.", post.content());
+        }
+
+        #[test]
+        fn it_upholds_invariants_in_every_state() {
+            let mut post = Post::new().with_required_rejections(2);
+            assert_invariants(&post);
+
+            post.add_text("I ate a salad for lunch today");
+            assert_invariants(&post);
+
+            post.request_review();
+            assert_invariants(&post);
+
+            post.reject();
+            assert_invariants(&post);
+
+            post.approve();
+            assert_invariants(&post);
+
+            post.approve();
+            assert_invariants(&post);
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_reports_roles_that_still_need_to_approve() {
+            let mut post = Post::new().with_required_roles(&["editor", "legal", "design"]);
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(post.approvals_needed_from_roles().is_empty());
+
+            post.request_review();
+            post.approve_by_role("alice", "legal");
+            assert_eq!(vec!["editor", "design"], post.approvals_needed_from_roles());
+
+            post.approve_by_role("bob", "design");
+            assert_eq!(vec!["editor"], post.approvals_needed_from_roles());
+
+            post.approve_by_role("carol", "editor");
+            assert!(post.approvals_needed_from_roles().is_empty());
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_sets_the_language_in_any_state() {
+            let post = Post::new();
+            assert_eq!("", post.language());
+
+            let mut post = post.with_language("en");
+            assert_eq!("en", post.language());
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            post.set_language("en-GB");
+            assert_eq!("en-GB", post.language());
+        }
+
+        #[test]
+        fn it_exports_markdown_with_frontmatter() {
+            let mut post = Post::new().with_language("en");
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!(
+                "---\nlanguage: \"en\"\n---\n",
+                post.to_markdown_with_frontmatter()
+            );
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "---\nlanguage: \"en\"\n---\nI ate a salad for lunch today",
+                post.to_markdown_with_frontmatter()
+            );
+            assert_eq!("---\n---\n", Post::new().to_markdown_with_frontmatter());
+        }
+
+        #[test]
+        fn it_flags_reviews_pending_too_long() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+            let max = Duration::from_secs(60 * 60);
+
+            post.add_text("I ate a salad for lunch today");
+            clock.advance(max * 2);
+            assert!(!post.is_review_overdue(max));
+
+            post.request_review();
+            clock.advance(max);
+            assert!(!post.is_review_overdue(max));
+
+            clock.advance(Duration::from_secs(1));
+            assert!(post.is_review_overdue(max));
+
+            post.approve();
+            post.approve();
+            assert!(!post.is_review_overdue(max));
+        }
+
+        #[test]
+        fn it_normalizes_newlines_when_enabled() {
+            let mut post = Post::new().with_normalize_newlines(true);
+
+            post.add_text("I ate a salad\r\nfor lunch\rtoday\n");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad\nfor lunch\ntoday\n", post.content());
+
+            let mut post = Post::new();
+            post.add_text("I ate a salad\r\nfor lunch");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad\r\nfor lunch", post.content());
+        }
+
+        #[test]
+        fn it_derives_a_slug_from_the_title() {
+            let post = Post::new().with_title("Salad, Steak & Cake: A Day of Eating!");
+            assert_eq!("salad-steak-cake-a-day-of-eating", post.slug());
+
+            let post = Post::new().with_title("  What's  for -- dinner? ");
+            assert_eq!("whats-for-dinner", post.slug());
+        }
+
+        #[test]
+        fn it_keeps_only_ascii_in_a_slug() {
+            let post = Post::new().with_title("Café über 中文 2024");
+            assert_eq!("caf-ber-2024", post.slug());
+        }
+
+        #[test]
+        fn it_builds_a_share_url_from_the_slug() {
+            let post = Post::new().with_title("Lunch Today");
+
+            assert_eq!(
+                "https://blog.example.com/posts/lunch-today",
+                post.share_url("https://blog.example.com/posts/")
+            );
+            assert_eq!(
+                "https://blog.example.com/lunch-today",
+                post.share_url("https://blog.example.com")
+            );
+        }
+
+        #[test]
+        fn it_includes_the_title_in_frontmatter() {
+            let post = Post::new().with_title("Lunch Today").with_language("en");
+
+            assert_eq!(
+                "---\ntitle: \"Lunch Today\"\nlanguage: \"en\"\n---\n",
+                post.to_markdown_with_frontmatter()
+            );
+        }
+
+        #[test]
+        fn it_includes_the_author_in_frontmatter() {
+            let post = Post::new()
+                .with_title("Lunch Today")
+                .with_author("alice")
+                .with_language("en");
+
+            assert_eq!(
+                "---\ntitle: \"Lunch Today\"\nauthor: \"alice\"\nlanguage: \"en\"\n---\n",
+                post.to_markdown_with_frontmatter()
+            );
+        }
+
+        #[test]
+        fn it_quotes_frontmatter_values() {
+            let post = Post::new().with_title("Lunch: a review").with_author("#me");
+
+            assert_eq!(
+                "---\ntitle: \"Lunch: a review\"\nauthor: \"#me\"\n---\n",
+                post.to_markdown_with_frontmatter()
+            );
+        }
+
+        #[test]
+        fn it_includes_the_tags_in_frontmatter() {
+            let mut post = Post::new().with_language("en");
+
+            post.add_tag("lunch");
+            post.add_tag("salad");
+            assert_eq!(
+                "---\nlanguage: \"en\"\ntags: [\"lunch\", \"salad\"]\n---\n",
+                post.to_markdown_with_frontmatter()
+            );
+        }
+
+        #[test]
+        fn it_summarizes_with_a_custom_ellipsis() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!("", post.summary_with(10, "…"));
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a…", post.summary_with(9, "…"));
+            assert_eq!("I ate a salad...", post.summary_with(16, "..."));
+            assert_eq!("I ate [more]", post.summary_with(12, " [more]"));
+            assert_eq!(
+                "I ate a salad for lunch today",
+                post.summary_with(29, "...")
+            );
+        }
+
+        #[test]
+        fn it_cuts_an_ellipsis_longer_than_the_summary_limit() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("..", post.summary_with(2, "..."));
+            assert_eq!("...", post.summary_with(3, "..."));
+            assert_eq!("", post.summary_with(0, "…"));
+        }
+
+        #[test]
+        fn it_replays_commands_like_manual_calls() {
+            let mut replayed = Post::new();
+            replayed.apply_all([
+                Command::AddText(String::from("I ate a salad for lunch today")),
+                Command::RequestReview,
+                Command::Reject,
+                Command::AddText(String::from(" and a steak for dinner")),
+                Command::RequestReview,
+                Command::Approve,
+                Command::Approve,
+            ]);
+
+            let mut manual = Post::new();
+            manual.add_text("I ate a salad for lunch today");
+            manual.request_review();
+            manual.reject();
+            manual.add_text(" and a steak for dinner");
+            manual.request_review();
+            manual.approve();
+            manual.approve();
+
+            assert_eq!(manual.state(), replayed.state());
+            assert_eq!(manual.content(), replayed.content());
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                replayed.content()
+            );
+        }
+
+        #[test]
+        fn it_records_commands_for_replay() {
+            let mut post = Post::new();
+            post.add_text("Not recorded. ");
+            assert!(post.recorded_commands().is_empty());
+
+            post.start_recording();
+            post.add_paragraph("I ate a salad for lunch today");
+            post.request_review();
+            post.reject();
+            post.add_text(" and a steak for dinner");
+            post.retry_review();
+            post.approve();
+            post.approve();
+
+            let mut replayed = Post::new();
+            replayed.add_text("Not recorded. ");
+            replayed.apply_all(post.recorded_commands().to_vec());
+
+            assert_eq!(post.state(), replayed.state());
+            assert_eq!(post.content(), replayed.content());
+            assert_eq!(
+                "Not recorded. \n\nI ate a salad for lunch today and a steak for dinner",
+                replayed.content()
+            );
+        }
+
+        #[test]
+        fn it_reports_content_stats() {
+            let mut post = Post::new();
+            assert_eq!(0, post.stats().reading_time_minutes);
+
+            post.add_text("Comí una ensalada 🥗\n");
+            post.add_text(&"word ".repeat(200));
+
+            assert_eq!(
+                ContentStats {
+                    chars: 1020,
+                    bytes: 1024,
+                    words: 204,
+                    lines: 2,
+                    reading_time_minutes: 2,
+                },
+                post.stats()
+            );
+        }
+
+        #[test]
+        fn it_publishes_when_the_guard_passes() {
+            let mut post = Post::new().with_publish_guard(|content| !content.contains("darn"));
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(Ok(()), post.try_approve());
+            assert_eq!(Ok(()), post.try_approve());
+
+            assert_eq!(PostState::Published, post.state());
+            assert_eq!(0, post.publish_vetoes());
+        }
+
+        #[test]
+        fn it_stays_pending_when_the_guard_vetoes() {
+            let mut post = Post::new().with_publish_guard(|content| !content.contains("darn"));
+
+            post.add_text("I ate a darn salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(PostState::PendingReview, post.state());
+            assert_eq!(1, post.publish_vetoes());
+
+            assert_eq!(Err(PostError::PublishVetoed), post.try_approve());
+            assert_eq!(PostState::PendingReview, post.state());
+            assert_eq!(2, post.publish_vetoes());
+            assert_eq!("", post.content());
+        }
+
+        #[test]
+        fn it_counts_each_named_reviewer_once() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by("alice");
+            post.approve_by("alice");
+            assert_eq!(PostState::PendingReview, post.state());
+
+            post.approve_by("bob");
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_merges_approvals_from_a_duplicate_post() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by("alice");
+
+            let mut draft = Post::new();
+            draft.add_text("I ate a salad for lunch today");
+            draft.merge_approvals_from(&post);
+            draft.request_review();
+            draft.approve_by("bob");
+            assert_eq!(PostState::PendingReview, draft.state());
+
+            let mut other = Post::new();
+            other.add_text("I ate a salad for lunch today");
+            other.request_review();
+            other.approve_by("alice");
+
+            post.merge_approvals_from(&other);
+            assert_eq!(PostState::PendingReview, post.state());
+
+            let mut reviewed_by_bob = Post::new();
+            reviewed_by_bob.add_text("I ate a salad for lunch today");
+            reviewed_by_bob.request_review();
+            reviewed_by_bob.approve_by("bob");
+
+            post.merge_approvals_from(&reviewed_by_bob);
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_strips_a_leading_byte_order_mark_by_default() {
+            let mut post = Post::new();
+
+            post.add_text("\u{FEFF}I ate a salad for lunch today");
+            post.add_text("\u{FEFF} and a steak for dinner");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                post.content()
+            );
+
+            let mut post = Post::new().with_strip_bom(false);
+            post.add_text("\u{FEFF}I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("\u{FEFF}I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_records_accepted_actions_in_history() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+
+            post.add_text("I ate a salad for lunch today");
+            post.approve();
+            post.request_review();
+            clock.advance(Duration::from_secs(1));
+            post.approve();
+
+            assert_eq!(
+                [
+                    Transition {
+                        action: Action::RequestReview,
+                        from: PostState::Draft,
+                        to: PostState::PendingReview,
+                        at: SystemTime::UNIX_EPOCH,
+                    },
+                    Transition {
+                        action: Action::Approve,
+                        from: PostState::PendingReview,
+                        to: PostState::PendingReview,
+                        at: SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+                    },
+                ],
+                post.history()
+            );
+        }
+
+        #[test]
+        fn it_iterates_over_history_lazily() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.reject();
+            post.request_review();
+            post.approve();
+            post.reject();
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            let rejections: Vec<&Transition> = post
+                .history_iter()
+                .filter(|transition| transition.action == Action::Reject)
+                .collect();
+            assert_eq!(2, rejections.len());
+            assert!(rejections
+                .iter()
+                .all(|transition| transition.to == PostState::Draft));
+            assert_eq!(8, post.history_iter().count());
+        }
+
+        #[test]
+        fn it_estimates_when_a_pending_post_will_publish() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+            let interval = Duration::from_secs(60 * 60);
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!(None, post.estimated_publish_time(interval));
+
+            post.request_review();
+            assert_eq!(
+                Some(SystemTime::UNIX_EPOCH + interval * 2),
+                post.estimated_publish_time(interval)
+            );
+
+            clock.advance(Duration::from_secs(10));
+            post.approve();
+            assert_eq!(
+                Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10) + interval),
+                post.estimated_publish_time(interval)
+            );
+
+            post.approve();
+            assert_eq!(None, post.estimated_publish_time(interval));
+        }
+
+        #[test]
+        fn it_extracts_paragraphs_from_published_content() {
+            let mut post = Post::new();
+
+            post.add_text("  I ate a salad\nfor lunch today \n\n\n \nI had a steak for dinner\n\n");
+            post.add_text("And cake for dessert");
+            assert!(post.paragraphs().is_empty());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                vec![
+                    "I ate a salad\nfor lunch today",
+                    "I had a steak for dinner",
+                    "And cake for dessert",
+                ],
+                post.paragraphs()
+            );
+        }
+
+        #[test]
+        fn it_prefers_an_overridden_slug() {
+            let mut post = Post::new().with_title("Lunch Today");
+            assert_eq!("lunch-today", post.slug());
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            post.set_slug("salad-day");
+            assert_eq!("salad-day", post.slug());
+            assert_eq!(
+                "https://blog.example.com/salad-day",
+                post.share_url("https://blog.example.com")
+            );
+        }
+
+        #[test]
+        fn it_extracts_headings_from_published_content() {
+            let mut post = Post::new();
+
+            post.add_text("# Meals\nI ate well.\n## Lunch\nA salad.\n### Dressing\n#hashtag\n####### Too deep\n## Dinner");
+            assert!(post.headings().is_empty());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                vec![
+                    (1, String::from("Meals")),
+                    (2, String::from("Lunch")),
+                    (3, String::from("Dressing")),
+                    (2, String::from("Dinner")),
+                ],
+                post.headings()
+            );
+        }
+
+        #[test]
+        fn it_finds_no_headings_in_plain_content() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert!(post.headings().is_empty());
+        }
+
+        #[test]
+        fn it_carries_an_id() {
+            assert_eq!(None, Post::new().id());
+
+            let post = Post::new().with_id(42).with_title("Lunch Today");
+            assert_eq!(Some(42), post.id());
+            assert_eq!(
+                "---\nid: 42\ntitle: \"Lunch Today\"\n---\n",
+                post.to_markdown_with_frontmatter()
+            );
+        }
+
+        #[test]
+        fn it_appends_text_from_a_reader() {
+            let mut post = Post::new();
+            post.add_text("I ate a salad");
+
+            let mut reader: &[u8] = " for lunch today 🥗".as_bytes();
+            assert_eq!(21, post.add_from_reader(&mut reader).unwrap());
+
+            post.request_review();
+            let mut reader: &[u8] = b" and a steak for dinner";
+            assert_eq!(0, post.add_from_reader(&mut reader).unwrap());
+            assert_eq!(23, reader.len());
+
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad for lunch today 🥗", post.content());
+        }
+
+        #[test]
+        fn it_rejects_invalid_utf8_from_a_reader() {
+            let mut post = Post::new();
+
+            let mut reader: &[u8] = &[0xff, 0xfe];
+            assert!(post.add_from_reader(&mut reader).is_err());
+            assert_eq!(0, post.byte_len());
+        }
+
+        #[test]
+        fn it_diffs_a_revision_with_the_previous_version() {
+            let mut post = Post::new();
+
+            post.add_text("Lunch\nI ate a salad");
+            post.revise("Not published yet");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(1, post.version());
+            assert!(post.diff_with_previous().is_empty());
+
+            post.revise("Lunch\nI ate a salad\nIt was delicious");
+            post.revise("Lunch today\nI ate a salad\nIt was delicious");
+            assert_eq!(3, post.version());
+            assert_eq!(
+                "Lunch today\nI ate a salad\nIt was delicious",
+                post.content()
+            );
+            assert_eq!(
+                vec![
+                    DiffOp::Delete(String::from("Lunch")),
+                    DiffOp::Insert(String::from("Lunch today")),
+                    DiffOp::Equal(String::from("I ate a salad")),
+                    DiffOp::Equal(String::from("It was delicious")),
+                ],
+                post.diff_with_previous()
+            );
+        }
+
+        #[test]
+        fn it_extracts_bare_links() {
+            let mut post = Post::new();
+
+            post.add_text("Recipes at https://example.com/salad, and http://example.org/steak.");
+            assert!(post.links().is_empty());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                vec!["https://example.com/salad", "http://example.org/steak"],
+                post.links()
+            );
+        }
+
+        #[test]
+        fn it_extracts_markdown_links() {
+            let mut post = Post::new();
+
+            post.add_text("See [the recipe](https://example.com/salad) and [notes](/notes).");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(vec!["https://example.com/salad", "/notes"], post.links());
+        }
+
+        #[test]
+        fn it_extracts_a_link_used_as_its_own_text_once() {
+            let mut post = Post::new();
+
+            post.add_text("See [https://a.com](https://a.com).");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(vec!["https://a.com"], post.links());
+        }
+
+        #[test]
+        fn it_lists_allowlisted_reviewers_who_have_not_acted() {
+            let mut post = Post::new()
+                .with_required_rejections(2)
+                .with_reviewer_allowlist(&["alice", "bob", "carol"]);
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(post.reviewers_pending().is_empty());
+
+            post.request_review();
+            assert_eq!(vec!["alice", "bob", "carol"], post.reviewers_pending());
+
+            post.approve_by("alice");
+            post.reject_by("bob");
+            post.approve_by("mallory");
+            assert_eq!(vec!["carol"], post.reviewers_pending());
+            assert_eq!(PostState::PendingReview, post.state());
+
+            post.approve_by("carol");
+            assert!(post.reviewers_pending().is_empty());
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_lists_no_pending_reviewers_without_an_allowlist() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert!(post.reviewers_pending().is_empty());
+        }
+
+        #[test]
+        fn it_title_cases_headings_when_enabled() {
+            let content =
+                "# what i ate today\nI ate a salad for lunch today\n## dinner and DESSERT\n";
+
+            let mut post = Post::new().with_title_case_headings(true);
+            post.add_text(content);
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "# What I Ate Today\nI ate a salad for lunch today\n## Dinner And Dessert\n",
+                post.content()
+            );
+
+            let mut post = Post::new();
+            post.add_text(content);
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(content, post.content());
+        }
+
+        #[test]
+        fn it_forks_a_post_with_its_state_and_approvals() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+
+            let mut fork = post.fork();
+            assert_eq!(PostState::PendingReview, fork.state());
+
+            fork.approve();
+            assert_eq!("I ate a salad for lunch today", fork.content());
+            assert_eq!(PostState::PendingReview, post.state());
+        }
+
+        #[test]
+        fn it_splits_published_content_into_sentences() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today.  Was it good?\nYes!  It cost $4.50 and ");
+            post.add_text("came with bread");
+            assert!(post.sentences().is_empty());
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                vec![
+                    "I ate a salad for lunch today.",
+                    "Was it good?",
+                    "Yes!",
+                    "It cost $4.50 and came with bread",
+                ],
+                post.sentences()
+            );
+        }
+
+        #[test]
+        fn it_notifies_role_subscribers_of_their_review_requests() {
+            let editor = Rc::new(Inbox::default());
+            let legal = Rc::new(Inbox::default());
+            let design = Rc::new(Inbox::default());
+            let mut post = Post::new().with_required_roles(&["editor", "legal"]);
+            post.subscribe_role("editor", editor.clone());
+            post.subscribe_role("legal", legal.clone());
+            post.subscribe_role("design", design.clone());
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.request_review();
+
+            assert_eq!(
+                vec![PostEvent::ReviewRequested {
+                    role: String::from("editor")
+                }],
+                *editor.events.borrow()
+            );
+            assert_eq!(
+                vec![PostEvent::ReviewRequested {
+                    role: String::from("legal")
+                }],
+                *legal.events.borrow()
+            );
+            assert!(design.events.borrow().is_empty());
+        }
+
+        #[test]
+        fn it_highlights_whole_word_matches() {
+            let mut post = Post::new();
+
+            post.add_text("Salad first: a salad, then saladbar and ensalada. SALAD!");
+            assert_eq!("", post.highlight("salad", "<mark>", "</mark>"));
+
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "**Salad** first: a **salad**, then saladbar and ensalada. **SALAD**!",
+                post.highlight("salad", "**", "**")
+            );
+        }
+
+        #[test]
+        fn it_is_terminal_only_once_published() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(!post.is_terminal());
+
+            post.request_review();
+            post.approve();
+            assert!(!post.is_terminal());
+
+            post.approve();
+            assert!(post.is_terminal());
+        }
+
+        #[test]
+        fn it_allows_review_under_the_average_sentence_length() {
+            let mut post = Post::new().with_max_avg_sentence_words(4);
+
+            post.add_text("I ate a salad. It was for lunch today.");
+            assert_eq!(4.5, post.avg_sentence_length());
+            post.request_review();
+            assert_eq!(PostState::Draft, post.state());
+
+            post.add_text(" Yum!");
+            assert_eq!(10.0 / 3.0, post.avg_sentence_length());
+            post.request_review();
+            assert_eq!(PostState::PendingReview, post.state());
+        }
+
+        #[test]
+        fn it_blocks_review_over_the_average_sentence_length() {
+            let mut post = Post::new().with_max_avg_sentence_words(5);
+
+            post.add_text("I ate a salad for lunch today and a steak for dinner.");
+            assert_eq!(12.0, post.avg_sentence_length());
+
+            post.request_review();
+            assert_eq!(PostState::Draft, post.state());
+        }
+
+        #[test]
+        fn it_publishes_notes_after_one_approval() {
+            let mut post = Post::new().with_type(PostType::Note);
+            assert_eq!(PostType::Note, post.post_type());
+
+            post.add_text("Back in five minutes");
+            post.request_review();
+            post.approve();
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_only_changes_type_before_review() {
+            let mut post = Post::new();
+            assert_eq!(PostType::Article, post.post_type());
+
+            post.add_text("I ate a salad for lunch today");
+            post.set_type(PostType::Announcement);
+            post.request_review();
+            post.set_type(PostType::Note);
+            assert_eq!(PostType::Announcement, post.post_type());
+
+            post.approve();
+            assert_eq!(PostState::PendingReview, post.state());
+            post.approve();
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_gives_duplicate_headings_distinct_anchors() {
+            let mut post = Post::new();
+
+            post.add_text("# Lunch Today!\n## Notes\nA salad.\n## Notes\nA steak.\n## Notes");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(
+                vec![
+                    (String::from("lunch-today"), String::from("Lunch Today!")),
+                    (String::from("notes"), String::from("Notes")),
+                    (String::from("notes-1"), String::from("Notes")),
+                    (String::from("notes-2"), String::from("Notes")),
+                ],
+                post.heading_anchors()
+            );
+        }
+
+        #[test]
+        fn it_distinguishes_cancelled_reviews_from_rejections() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.cancel_review();
+            post.request_review();
+            post.approve();
+            post.cancel_review();
+            assert_eq!(PostState::Draft, post.state());
+
+            post.add_text(" and a steak for dinner");
+            post.request_review();
+            post.reject();
+            post.request_review();
+            post.approve();
+            assert_eq!(PostState::PendingReview, post.state());
+            post.approve();
+
+            let actions: Vec<Action> = post.history_iter().map(|t| t.action).collect();
+            assert_eq!(
+                vec![
+                    Action::RequestReview,
+                    Action::Approve,
+                    Action::CancelReview,
+                    Action::RequestReview,
+                    Action::Reject,
+                    Action::RequestReview,
+                    Action::Approve,
+                    Action::Approve,
+                ],
+                actions
+            );
+            assert_eq!(
+                "I ate a salad for lunch today and a steak for dinner",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_strips_emoji_when_enabled() {
+            let content = "I ate a salad 🥗 for lunch ☀️ today 👩‍🍳!";
+
+            let mut post = Post::new().with_strip_emoji(true);
+            post.add_text(content);
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("I ate a salad  for lunch  today !", post.content());
+
+            let mut post = Post::new();
+            post.add_text(content);
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(content, post.content());
+        }
+
+        #[test]
+        fn it_lists_the_actions_available_in_each_state() {
+            let mut post = Post::new().with_max_review_words(6);
+
+            post.add_text("I ate a salad for lunch");
+            assert_eq!(vec![Action::RequestReview], post.available_actions());
+
+            post.request_review();
+            assert_eq!(
+                vec![Action::Approve, Action::Reject, Action::CancelReview],
+                post.available_actions()
+            );
+
+            post.approve();
+            post.approve();
+            assert!(post.available_actions().is_empty());
+        }
+
+        #[test]
+        fn it_hides_review_when_the_draft_is_not_ready() {
+            let mut post = Post::new().with_max_review_words(1);
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(post.available_actions().is_empty());
+        }
+
+        #[test]
+        fn it_suggests_tags_from_frequent_words() {
+            let mut post = Post::new();
+            assert!(post.suggest_tags(3).is_empty());
+
+            post.add_text("Salad for lunch. The salad was fresh, and the dressing on the ");
+            post.add_text("Salad was tangy. Lunch was late.");
+
+            assert_eq!(
+                vec![
+                    String::from("salad"),
+                    String::from("lunch"),
+                    String::from("fresh")
+                ],
+                post.suggest_tags(3)
+            );
+        }
+
+        #[test]
+        fn it_stops_revising_at_the_revision_limit() {
+            let mut post = Post::new().with_max_revisions(2);
+
+            post.add_text("Version 1");
+            assert_eq!(Err(PostError::NotPublished), post.try_revise("Too early"));
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(Some(2), post.revisions_remaining());
+
+            post.revise("Version 2");
+            assert_eq!(Ok(()), post.try_revise("Version 3"));
+            assert_eq!(Some(0), post.revisions_remaining());
+
+            post.revise("Version 4");
+            assert_eq!(
+                Err(PostError::RevisionLimitReached),
+                post.try_revise("Version 4")
+            );
+            assert_eq!("Version 3", post.content());
+            assert_eq!(3, post.version());
+        }
+
+        #[test]
+        fn it_revises_without_limit_by_default() {
+            let mut post = Post::new();
+
+            post.add_text("Version 1");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(None, post.revisions_remaining());
+
+            for version in 2..=10 {
+                post.revise(&format!("Version {}", version));
+            }
+            assert_eq!(10, post.version());
+        }
+
+        #[test]
+        fn it_scores_simple_prose_as_easier_to_read() {
+            let mut simple = Post::new();
+            assert_eq!(0.0, simple.reading_ease());
+            simple.add_text("I ate a salad. It was good. The sun was out.");
+
+            let mut complex = Post::new();
+            complex.add_text(
+                "Notwithstanding considerable institutional reluctance, the administration \
+                 ultimately implemented comprehensive organizational restructuring initiatives.",
+            );
+
+            assert!(simple.reading_ease() > 90.0);
+            assert!(complex.reading_ease() < 10.0);
+        }
+
+        #[test]
+        fn it_imports_markdown_files_as_published_posts() {
+            let posts = import_markdown(&[
+                ("Lunch", "# Lunch\nI ate a salad."),
+                ("Dinner", "# Dinner\nI had a steak."),
+            ]);
+
+            assert_eq!(2, posts.len());
+            assert_eq!("Lunch", posts[0].title());
+            assert_eq!(PostState::Published, posts[0].state());
+            assert_eq!("# Lunch\nI ate a salad.", posts[0].content());
+            assert_eq!("Dinner", posts[1].title());
+            assert_eq!(PostState::Published, posts[1].state());
+            assert_eq!("# Dinner\nI had a steak.", posts[1].content());
+        }
+
+        #[test]
+        fn it_trims_content_when_published() {
+            let mut post = Post::new().with_trim_on_publish(true);
+
+            post.add_text("\n  I ate a salad for lunch today\n\n\n");
+            post.request_review();
+            post.approve();
+            assert_eq!(35, post.byte_len());
+
+            post.approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+            assert_eq!(29, post.byte_len());
+        }
+
+        #[test]
+        fn it_merges_histories_in_chronological_order() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+            post.add_text("I ate a salad for lunch today");
+            let mut fork = post.fork();
+
+            clock.advance(Duration::from_secs(10));
+            post.request_review();
+            clock.advance(Duration::from_secs(10));
+            fork.request_review();
+            clock.advance(Duration::from_secs(10));
+            post.reject();
+            clock.advance(Duration::from_secs(10));
+            fork.approve();
+
+            post.merge_history_from(&fork);
+
+            let actions: Vec<Action> = post.history_iter().map(|t| t.action).collect();
+            assert_eq!(
+                Vec::from([
+                    Action::RequestReview,
+                    Action::RequestReview,
+                    Action::Reject,
+                    Action::Approve
+                ]),
+                actions
+            );
+            assert!(post
+                .history()
+                .windows(2)
+                .all(|pair| pair[0].at <= pair[1].at));
+        }
+
+        #[test]
+        fn it_allows_review_with_the_required_sections() {
+            let mut post =
+                Post::new().with_required_sections(&["## Introduction", "## Conclusion"]);
+
+            post.add_text("## Introduction\nI ate a salad.\n## Conclusion\nIt was lunch.\n");
+            assert!(post.missing_sections().is_empty());
+
+            post.request_review();
+            assert_eq!(PostState::PendingReview, post.state());
+        }
+
+        #[test]
+        fn it_blocks_review_without_the_required_sections() {
+            let mut post =
+                Post::new().with_required_sections(&["## Introduction", "## Conclusion"]);
+
+            post.add_text("## Introduction\nI ate a salad.\n### Conclusion\nIt was lunch.\n");
+            assert_eq!(
+                Vec::from(["## Conclusion".to_string()]),
+                post.missing_sections()
+            );
+
+            post.request_review();
+            assert_eq!(PostState::Draft, post.state());
+        }
+
+        #[test]
+        fn it_lists_no_overdue_reviewers_before_their_deadlines() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+            let day = Duration::from_secs(24 * 60 * 60);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.assign_reviewer("alice", SystemTime::UNIX_EPOCH + day);
+            post.assign_reviewer("bob", SystemTime::UNIX_EPOCH + day);
+
+            clock.advance(day);
+            assert!(post.overdue_reviewers().is_empty());
+        }
+
+        #[test]
+        fn it_lists_reviewers_past_their_deadlines() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new().with_clock(clock.clone());
+            let day = Duration::from_secs(24 * 60 * 60);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.assign_reviewer("alice", SystemTime::UNIX_EPOCH + day);
+            post.assign_reviewer("bob", SystemTime::UNIX_EPOCH + day);
+            post.assign_reviewer("carol", SystemTime::UNIX_EPOCH + day * 2);
+            post.approve_by("alice");
+
+            clock.advance(day + Duration::from_secs(1));
+            assert_eq!(vec!["bob"], post.overdue_reviewers());
+
+            post.approve_by("bob");
+            assert!(post.overdue_reviewers().is_empty());
+        }
+
+        #[test]
+        fn it_builds_a_nested_table_of_contents() {
+            let mut post = Post::new();
+
+            post.add_text("## Lunch\nA salad.\n### Dressing\n#### Oil\n### Notes\n## Dinner\n");
+            post.request_review();
+            assert_eq!("", post.table_of_contents());
+
+            post.approve();
+            post.approve();
+            assert_eq!(
+                "- [Lunch](#lunch)\n  - [Dressing](#dressing)\n    - [Oil](#oil)\n  - [Notes](#notes)\n- [Dinner](#dinner)\n",
+                post.table_of_contents()
+            );
+        }
+
+        #[test]
+        fn it_builds_an_empty_table_of_contents_without_headings() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!("", post.table_of_contents());
+        }
+
+        #[test]
+        fn it_reports_the_text_each_append_added() {
+            let deltas = Rc::new(RefCell::new(Vec::new()));
+            let inbox = Rc::new(Inbox::default());
+            let mut post = Post::new().with_normalize_newlines(true);
+            let seen = deltas.clone();
+            post.on_text_added(move |delta| seen.borrow_mut().push(delta.to_string()));
+            post.subscribe(inbox.clone());
+
+            post.add_text("I ate a salad\r\n");
+            post.add_text("for lunch today");
+            post.request_review();
+            post.add_text(" and a steak");
+
+            assert_eq!(
+                vec![
+                    String::from("I ate a salad\n"),
+                    String::from("for lunch today")
+                ],
+                *deltas.borrow()
+            );
+            assert_eq!(
+                vec![
+                    PostEvent::TextAdded(String::from("I ate a salad\n")),
+                    PostEvent::TextAdded(String::from("for lunch today")),
+                ],
+                *inbox.events.borrow()
+            );
+        }
+
+        #[test]
+        fn it_masks_profanity_at_the_start_of_a_sentence() {
+            let mut post = Post::new().with_profanity_filter(&["darn"], '*');
+
+            post.add_text("Darn, I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!("****, I ate a salad for lunch today", post.content());
+            assert_eq!(35, post.byte_len());
+        }
+
+        #[test]
+        fn it_masks_profanity_in_the_middle_of_a_sentence() {
+            let mut post = Post::new().with_profanity_filter(&["heck", "darn"], '#');
+
+            post.add_text("I ate a HECK of a salad, a darned big one, darn it");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "I ate a #### of a salad, a darned big one, #### it",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_publishes_after_one_heavyweight_approval() {
+            let mut post = Post::new().with_approval_weights(&[("alice", 2), ("bob", 1)]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(0, post.current_approval_weight());
+
+            post.approve_by("alice");
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_publishes_after_two_lightweight_approvals() {
+            let mut post = Post::new().with_approval_weights(&[("alice", 2), ("bob", 1)]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by("bob");
+            post.approve_by("bob");
+            assert_eq!(1, post.current_approval_weight());
+            assert_eq!(PostState::PendingReview, post.state());
+
+            post.approve_by("carol");
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_blocks_review_while_a_link_is_broken() {
+            let mut post = Post::new().with_link_validator(|link| !link.contains("dead"));
+
+            post.add_text(
+                "I ate a [salad](https://example.com/salad) at https://dead.example.com.",
+            );
+            assert_eq!(vec!["https://dead.example.com"], post.broken_links());
+
+            post.request_review();
+            assert_eq!(PostState::Draft, post.state());
+        }
+
+        #[test]
+        fn it_allows_review_when_every_link_is_valid() {
+            let mut post = Post::new().with_link_validator(|link| !link.contains("dead"));
+
+            post.add_text("I ate a [salad](https://example.com/salad) for lunch today");
+            assert!(post.broken_links().is_empty());
+
+            post.request_review();
+            assert_eq!(PostState::PendingReview, post.state());
+        }
+
+        #[test]
+        fn it_converts_a_published_post_into_a_feed_item() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new()
+                .with_clock(clock.clone())
+                .with_title("Lunch Today")
+                .with_author("Juan");
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            assert_eq!(None, post.to_feed_item());
+
+            clock.advance(Duration::from_secs(60));
+            post.approve();
+            assert_eq!(
+                Some(FeedItem {
+                    title: String::from("Lunch Today"),
+                    link: String::from("/lunch-today"),
+                    content: String::from("I ate a salad for lunch today"),
+                    published_at: SystemTime::UNIX_EPOCH + Duration::from_secs(60),
+                    author: String::from("Juan"),
+                    sticky: false,
+                }),
+                post.to_feed_item()
+            );
+        }
+
+        #[test]
+        fn it_curls_quotes_by_word_boundary() {
+            let mut post = Post::new().with_smart_quotes(true);
+
+            post.add_text("\"I'd eat a salad,\" she said ('twas 'lunch').");
+            post.request_review();
+            post.approve();
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!(
+                "“I’d eat a salad,” she said (‘twas ‘lunch’).",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_keeps_stored_quotes_straight() {
+            let mut post = Post::new().with_smart_quotes(true);
+
+            post.add_text("It's \"lunch\"");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!("It’s “lunch”", post.content());
+            assert_eq!(vec!["It's \"lunch\""], post.paragraphs());
+        }
+
+        #[test]
+        fn it_counts_visits_to_each_state() {
+            let mut post = Post::new();
+            assert_eq!(1, post.state_visit_count(PostState::Draft));
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.reject();
+            post.request_review();
+            post.approve();
+            post.reject();
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(3, post.state_visit_count(PostState::Draft));
+            assert_eq!(3, post.state_visit_count(PostState::PendingReview));
+            assert_eq!(1, post.state_visit_count(PostState::Published));
+        }
+
+        #[test]
+        fn it_lists_defined_footnotes() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad[^1] for lunch[^noon].\n\n[^1]: With olive oil.\n[^noon]: Around 12.\n");
+            post.request_review();
+            post.approve();
+            assert!(post.footnotes().is_empty());
+
+            post.approve();
+            assert_eq!(
+                vec![
+                    (String::from("1"), String::from("With olive oil.")),
+                    (String::from("noon"), String::from("Around 12.")),
+                ],
+                post.footnotes()
+            );
+            assert!(post.undefined_footnotes().is_empty());
+        }
+
+        #[test]
+        fn it_lists_undefined_footnotes() {
+            let mut post = Post::new();
+
+            post.add_text(
+                "I ate a salad[^1] for lunch[^2] and dinner[^3][^2].\n\n[^1]: With olive oil.\n",
+            );
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                vec![String::from("2"), String::from("3")],
+                post.undefined_footnotes()
+            );
+        }
+
+        #[test]
+        fn it_publishes_at_a_half_quorum_of_assigned_reviewers() {
+            let mut post = Post::new().with_review_quorum(0.5);
+            let deadline = SystemTime::UNIX_EPOCH;
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            for name in ["alice", "bob", "carol"] {
+                post.assign_reviewer(name, deadline);
+            }
+            assert_eq!(Some(2), post.quorum_target());
+
+            post.approve_by("alice");
+            post.approve_by("mallory");
+            assert_eq!(PostState::PendingReview, post.state());
+
+            post.approve_by("carol");
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_publishes_at_a_full_quorum_of_assigned_reviewers() {
+            let mut post = Post::new().with_review_quorum(1.0);
+            let deadline = SystemTime::UNIX_EPOCH;
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            for name in ["alice", "bob", "carol"] {
+                post.assign_reviewer(name, deadline);
+            }
+            assert_eq!(Some(3), post.quorum_target());
+
+            post.approve_by("alice");
+            post.approve_by("bob");
+            assert_eq!(PostState::PendingReview, post.state());
+
+            post.approve_by("carol");
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_applies_style_replacements_to_visible_content() {
+            let mut post = Post::new()
+                .with_style_replacements(&[("e-mail", "email"), ("web site", "website")]);
+
+            post.add_text("E-mail me about the Web Site, not the e-mails.");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "email me about the website, not the e-mails.",
+                post.content()
+            );
+            assert_eq!(
+                vec!["E-mail me about the Web Site, not the e-mails."],
+                post.paragraphs()
+            );
+        }
+
+        #[test]
+        fn it_clones_a_post_for_translation() {
+            let mut post = Post::new().with_id(7).with_language("en");
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            let mut translation = post.clone_for_translation("es");
+            assert_eq!(PostState::Draft, translation.state());
+            assert_eq!("es", translation.language());
+            assert_eq!(Some(7), translation.source_id());
+            assert_eq!(None, post.source_id());
+
+            translation.request_review();
+            translation.approve();
+            translation.approve();
+            assert_eq!("I ate a salad for lunch today", translation.content());
+        }
+
+        #[test]
+        fn it_renders_a_pipe_table_as_html() {
+            let mut post = Post::new();
+
+            post.add_text("## Meals\n| Meal | Calories |\n| :--- | ---: |\n| Salad | 350 |\n| Steak & fries | 900 |\n\nYum.");
+            post.request_review();
+            post.approve();
+            assert_eq!("", post.content_html());
+
+            post.approve();
+            assert_eq!(
+                "<h2>Meals</h2>\n\
+                 <table>\n<thead>\n\
+                 <tr><th align=\"left\">Meal</th><th align=\"right\">Calories</th></tr>\n\
+                 </thead>\n<tbody>\n\
+                 <tr><td align=\"left\">Salad</td><td align=\"right\">350</td></tr>\n\
+                 <tr><td align=\"left\">Steak &amp; fries</td><td align=\"right\">900</td></tr>\n\
+                 </tbody>\n</table>\n\
+                 <p>Yum.</p>\n",
+                post.content_html()
+            );
+        }
+
+        #[test]
+        fn it_auto_tags_an_untagged_post_on_publish() {
+            let mut post = Post::new().with_auto_tag(true);
+
+            post.add_text("Salad for lunch. The salad was fresh, and the dressing on the ");
+            post.add_text("Salad was tangy. Lunch was late.");
+            post.request_review();
+            post.approve();
+            assert!(post.tags().is_empty());
+
+            post.approve();
+            assert_eq!(
+                [
+                    String::from("salad"),
+                    String::from("lunch"),
+                    String::from("fresh")
+                ],
+                post.tags()
+            );
+        }
+
+        #[test]
+        fn it_keeps_manual_tags_when_auto_tagging() {
+            let mut post = Post::new().with_auto_tag(true);
+
+            post.add_text("Salad for lunch. The salad was fresh.");
+            post.add_tag("food");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!([String::from("food")], post.tags());
+        }
+
+        #[test]
+        fn it_skips_a_repeated_paragraph() {
+            let mut post = Post::new().with_dedup_paragraphs(true);
+
+            post.add_paragraph("I ate a salad for lunch today");
+            post.add_paragraph("I ate a salad for lunch today");
+            post.add_paragraph("And a steak for dinner");
+            post.add_paragraph("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "I ate a salad for lunch today\n\nAnd a steak for dinner\n\nI ate a salad for lunch today",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_reverts_to_an_earlier_revision() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert!(!post.revert_to_revision(1));
+            post.request_review();
+            post.approve();
+            post.approve();
+            post.revise("I ate a steak for lunch today");
+            post.revise("I ate a burger for lunch today");
+            assert_eq!(3, post.version());
+
+            assert!(!post.revert_to_revision(0));
+            assert!(!post.revert_to_revision(4));
+            assert!(post.revert_to_revision(1));
+            assert_eq!("I ate a salad for lunch today", post.content());
+            assert_eq!(4, post.version());
+        }
+
+        #[test]
+        fn it_counts_inline_citations() {
+            let mut post = Post::new();
+
+            post.add_text(
+                "Salads are healthy [1], filling [2][3] and see [4](https://example.com).",
+            );
+            assert_eq!(3, post.citation_count());
+        }
+
+        #[test]
+        fn it_counts_no_citations_without_numbered_brackets() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a [salad] for lunch[^1] today [].");
+            assert_eq!(0, post.citation_count());
+        }
+
+        #[test]
+        fn it_ignores_transitions_inside_the_cooldown() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new()
+                .with_clock(clock.clone())
+                .with_transition_cooldown(Duration::from_secs(60));
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!(Duration::ZERO, post.time_until_next_transition());
+            post.request_review();
+
+            clock.advance(Duration::from_secs(45));
+            assert_eq!(Duration::from_secs(15), post.time_until_next_transition());
+            post.approve();
+            assert_eq!(Err(PostError::CoolingDown), post.try_approve());
+            assert_eq!(1, post.history().len());
+        }
+
+        #[test]
+        fn it_allows_transitions_after_the_cooldown() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new()
+                .with_clock(clock.clone())
+                .with_transition_cooldown(Duration::from_secs(60));
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+
+            clock.advance(Duration::from_secs(60));
+            assert_eq!(Duration::ZERO, post.time_until_next_transition());
+            assert_eq!(Ok(()), post.try_approve());
+
+            clock.advance(Duration::from_secs(61));
+            post.approve();
+            assert_eq!(PostState::Published, post.state());
+        }
+
+        #[test]
+        fn it_lists_images_with_alt_text() {
+            let mut post = Post::new();
+
+            post.add_text("Lunch: ![A green salad](/img/salad.png) and ![Steak]( https://cdn.example.com/steak.jpg ).");
+            post.request_review();
+            post.approve();
+            assert!(post.images().is_empty());
+
+            post.approve();
+            assert_eq!(
+                vec![
+                    (
+                        String::from("A green salad"),
+                        String::from("/img/salad.png")
+                    ),
+                    (
+                        String::from("Steak"),
+                        String::from("https://cdn.example.com/steak.jpg")
+                    ),
+                ],
+                post.images()
+            );
+        }
+
+        #[test]
+        fn it_lists_images_without_alt_text() {
+            let mut post = Post::new();
+
+            post.add_text("![](/img/salad.png) is not a [link](/notes).");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                vec![(String::new(), String::from("/img/salad.png"))],
+                post.images()
+            );
+        }
+
+        #[test]
+        fn it_sorts_sticky_posts_to_the_top_of_the_feed() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut posts: Vec<Post> = ["old pinned", "old", "new", "new pinned", "draft"]
+                .iter()
+                .map(|title| {
+                    let mut post = Post::new().with_clock(clock.clone()).with_title(title);
+                    post.add_text("I ate a salad for lunch today");
+                    post
+                })
+                .collect();
+            for post in &mut posts[..4] {
+                clock.advance(Duration::from_secs(60));
+                post.request_review();
+                post.approve();
+                post.approve();
+            }
+            posts[0].set_sticky(true);
+            posts[3].set_sticky(true);
+            assert!(posts[3].to_feed_item().unwrap().sticky);
+
+            sort_feed(&mut posts);
+
+            let titles: Vec<&str> = posts.iter().map(|post| post.title()).collect();
+            assert_eq!(
+                vec!["new pinned", "old pinned", "new", "old", "draft"],
+                titles
+            );
+        }
+
+        #[test]
+        fn it_flags_words_missing_from_the_dictionary() {
+            let dictionary: HashSet<String> = ["I", "ate", "a", "salad", "with", "Kombucha"]
+                .iter()
+                .map(|word| word.to_string())
+                .collect();
+            let mut post = Post::new().with_dictionary(dictionary);
+
+            post.add_text("I ate a salda with kombucha, 2 salda!");
+            assert_eq!(vec![String::from("salda")], post.unknown_words());
+
+            post.request_review();
+            assert_eq!(PostState::PendingReview, post.state());
+        }
+
+        #[test]
+        fn it_applies_content_transforms_in_order() {
+            let mut post = Post::new();
+            post.add_content_transform(|text| text.replace("salad", "steak"));
+            post.add_content_transform(|text| format!("{}!", text.to_uppercase()));
+            post.add_content_transform(|text| text.replace("STEAK", "Steak"));
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            assert_eq!("", post.content());
+
+            post.approve();
+            assert_eq!("I ATE A Steak FOR LUNCH TODAY!", post.content());
+            assert_eq!(vec!["I ate a salad for lunch today"], post.paragraphs());
+        }
+
+        #[test]
+        fn it_reports_keyword_density() {
+            let mut post = Post::new();
+            assert_eq!(
+                vec![(String::from("salad"), 0.0)],
+                post.keyword_density(&["salad"])
+            );
+
+            post.add_text("Salad for lunch, salad!");
+            assert_eq!(
+                vec![(String::from("salad"), 0.5), (String::from("steak"), 0.0)],
+                post.keyword_density(&["Salad", "steak"])
+            );
+        }
+
+        #[test]
+        fn it_expires_drafts_past_their_ttl() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let ttl = Duration::from_secs(60);
+            let mut post = Post::new().with_clock(clock.clone()).with_draft_ttl(ttl);
+
+            post.add_text("I ate a salad for lunch today");
+            clock.advance(ttl);
+            assert!(!post.is_expired());
+
+            clock.advance(Duration::from_secs(1));
+            assert!(post.is_expired());
+
+            post.request_review();
+            assert!(!post.is_expired());
+        }
+
+        #[test]
+        fn it_purges_expired_drafts() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let ttl = Duration::from_secs(60);
+            let stale = Post::new()
+                .with_clock(clock.clone())
+                .with_draft_ttl(ttl)
+                .with_title("stale");
+            let mut pending = Post::new()
+                .with_clock(clock.clone())
+                .with_draft_ttl(ttl)
+                .with_title("pending");
+            pending.add_text("I ate a salad for lunch today");
+            pending.request_review();
+            clock.advance(Duration::from_secs(30));
+            let fresh = Post::new()
+                .with_clock(clock.clone())
+                .with_draft_ttl(ttl)
+                .with_title("fresh");
+            let mut posts = Vec::from([stale, pending, fresh]);
+
+            clock.advance(Duration::from_secs(31));
+            purge_expired(&mut posts);
+
+            let titles: Vec<&str> = posts.iter().map(|post| post.title()).collect();
+            assert_eq!(vec!["pending", "fresh"], titles);
+        }
+
+        #[test]
+        fn it_prefixes_relative_urls_with_the_base_url() {
+            let mut post = Post::new().with_base_url("https://example.com/blog/");
+
+            post.add_text("I ate a [salad](/recipes/salad) ![Salad](img/salad.png) [^1] today");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "<p>I ate a <a href=\"https://example.com/blog/recipes/salad\">salad</a> \
+                 <img src=\"https://example.com/blog/img/salad.png\" alt=\"Salad\"> [^1] today</p>\n",
+                post.content_html()
+            );
+        }
+
+        #[test]
+        fn it_keeps_absolute_urls_with_a_base_url() {
+            let mut post = Post::new().with_base_url("https://example.com/blog");
+
+            post.add_text("See [recipes](https://cook.example.com/salad), [notes](#notes) and [me](mailto:juan@example.com).");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "<p>See <a href=\"https://cook.example.com/salad\">recipes</a>, \
+                 <a href=\"#notes\">notes</a> and <a href=\"mailto:juan@example.com\">me</a>.</p>\n",
+                post.content_html()
+            );
+        }
+
+        #[test]
+        fn it_keeps_urls_with_any_scheme_with_a_base_url() {
+            let mut post = Post::new().with_base_url("https://example.com/blog");
+
+            post.add_text("[Call](tel:+123), [run](javascript:go) and [dot](data:text/plain,hi)");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            let html = post.content_html();
+            assert!(html.contains("href=\"tel:+123\""), "{}", html);
+            assert!(html.contains("href=\"javascript:go\""), "{}", html);
+            assert!(html.contains("href=\"data:text/plain,hi\""), "{}", html);
+            assert!(!html.contains("example.com"), "{}", html);
+        }
+
+        #[test]
+        fn it_reports_approval_progress() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+            assert_eq!(0.0, post.approval_progress());
+
+            post.request_review();
+            assert_eq!(0.0, post.approval_progress());
+            post.approve();
+            assert_eq!(0.5, post.approval_progress());
+
+            post.approve();
+            assert_eq!(PostState::Published, post.state());
+            assert_eq!(1.0, post.approval_progress());
+        }
+
+        #[test]
+        fn it_clamps_approval_progress_while_roles_are_outstanding() {
+            let mut post = Post::new()
+                .with_approval_weights(&[("alice", 3)])
+                .with_required_roles(&["legal"]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by("alice");
+            assert_eq!(PostState::PendingReview, post.state());
+            assert_eq!(1.0, post.approval_progress());
+        }
+
+        #[test]
+        fn it_reports_quorum_progress() {
+            let mut post = Post::new().with_review_quorum(1.0);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            for name in ["alice", "bob", "carol", "dave"] {
+                post.assign_reviewer(name, SystemTime::UNIX_EPOCH);
+            }
+            post.approve_by("alice");
+            post.approve_by("mallory");
+            post.approve_by("carol");
+            assert_eq!(0.5, post.approval_progress());
+        }
+
+        #[test]
+        fn it_extracts_fenced_code_blocks() {
+            let mut post = Post::new();
+
+            post.add_text("Lunch in Rust:\n```rust\nfn main() {\n    println!(\"salad\");\n}\n```\nAnd plain:\n```\nsalad\n```\n");
+            post.request_review();
+            post.approve();
+            assert!(post.code_blocks().is_empty());
+
+            post.approve();
+            assert_eq!(
+                vec![
+                    (
+                        Some(String::from("rust")),
+                        String::from("fn main() {\n    println!(\"salad\");\n}")
+                    ),
+                    (None, String::from("salad")),
+                ],
+                post.code_blocks()
+            );
+        }
+
+        #[test]
+        fn it_serializes_a_feed_item_as_json() {
+            let item = FeedItem {
+                title: String::from("Lunch \"Today\""),
+                link: String::from("/lunch-today"),
+                content: String::from("I ate a salad\nfor lunch"),
+                published_at: SystemTime::UNIX_EPOCH + Duration::from_secs(60),
+                author: String::from("Juan"),
+                sticky: true,
+            };
+
+            assert_eq!(
+                "{\"title\":\"Lunch \\\"Today\\\"\",\"link\":\"/lunch-today\",\
+                 \"content\":\"I ate a salad\\nfor lunch\",\"published_at\":60,\
+                 \"author\":\"Juan\",\"sticky\":true}",
+                item.to_json()
+            );
+        }
+
+        #[cfg(feature = "webhook")]
+        #[test]
+        fn it_sends_the_feed_item_to_the_webhook_on_publish() {
+            let sent = Rc::new(RefCell::new(Vec::new()));
+            let outbox = sent.clone();
+            let mut post = Post::new()
+                .with_title("Lunch")
+                .with_webhook(String::from("http://hooks.example.com/posts"))
+                .with_webhook_sender(move |url, body| {
+                    outbox
+                        .borrow_mut()
+                        .push((url.to_string(), body.to_string()));
+                    Ok(())
+                });
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve();
+            assert!(sent.borrow().is_empty());
+
+            post.approve();
+            assert_eq!(
+                vec![(
+                    String::from("http://hooks.example.com/posts"),
+                    post.to_feed_item().unwrap().to_json()
+                )],
+                *sent.borrow()
+            );
+            assert!(sent.borrow()[0]
+                .1
+                .contains("\"content\":\"I ate a salad for lunch today\""));
+        }
+
+        #[test]
+        fn it_normalizes_heading_levels_in_visible_content() {
+            let mut post = Post::new().with_normalized_headings(true);
+
+            post.add_text("### Lunch\nA salad.\n#### Dressing\n### Dinner\n##### Dessert\n");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "# Lunch\nA salad.\n## Dressing\n# Dinner\n### Dessert\n",
+                post.content()
+            );
+            assert_eq!(
+                vec!["### Lunch\nA salad.\n#### Dressing\n### Dinner\n##### Dessert"],
+                post.paragraphs()
+            );
+        }
+
+        #[test]
+        fn it_round_trips_posts_through_an_export() {
+            let mut draft = Post::new().with_id(1).with_title("Draft");
+            draft.add_text("A \"quoted\"\n\tline\u{1}, ünïcode \\ done");
+            let mut pending = Post::new().with_title("Pending").with_author("Juan");
+            pending.add_text("I ate a salad for lunch today");
+            pending.request_review();
+            pending.approve_by("alice");
+            let mut published = Post::new().with_id(u64::MAX).with_language("es");
+            published.add_text("Comí una ensalada");
+            published.request_review();
+            published.approve();
+            published.approve();
+
+            let json = export_all(&[draft, pending, published]);
+            let mut posts = import_all(&json).unwrap();
+
+            assert_eq!(json, export_all(&posts));
+            assert_eq!(3, posts.len());
+            assert_eq!(PostState::Draft, posts[0].state());
+            assert_eq!(Some(1), posts[0].id());
+            assert_eq!(PostState::PendingReview, posts[1].state());
+            assert_eq!("Juan", posts[1].author());
+            assert_eq!(PostState::Published, posts[2].state());
+            assert_eq!(Some(u64::MAX), posts[2].id());
+            assert_eq!("Comí una ensalada", posts[2].content());
+
+            posts[1].approve_by("alice");
+            assert_eq!(PostState::PendingReview, posts[1].state());
+            posts[1].approve_by("bob");
+            assert_eq!(PostState::Published, posts[1].state());
+        }
+
+        #[test]
+        fn it_refuses_to_import_malformed_exports() {
+            assert!(import_all("[]").unwrap().is_empty());
+            assert_eq!(Some(PostError::InvalidExport), import_all("[{").err());
+            assert_eq!(
+                Some(PostError::InvalidExport),
+                import_all("[{\"title\":\"x\"}]").err()
+            );
+        }
+
+        #[test]
+        fn it_saturates_the_approval_count_while_roles_are_outstanding() {
+            let mut post = Post::new().with_required_roles(&["legal"]);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            for _ in 0..300 {
+                post.approve();
+            }
+            assert_eq!(PostState::PendingReview, post.state());
+            assert_eq!(u8::MAX as u32, post.current_approval_weight());
+        }
+
+        #[test]
+        fn it_renders_placeholders_over_the_visible_view() {
+            let mut post = Post::new().with_redactions(&["secret"]).with_embargo(3);
+            let vars = HashMap::from([("day", String::from("Monday"))]);
+
+            post.add_text("On {{day}} the secret plan.");
+            post.request_review();
+            post.approve();
+            post.approve();
+            assert_eq!(post.content(), post.render_with(&vars));
+
+            post.lift_embargo();
+            assert_eq!("On Monday the ██████ plan.", post.render_with(&vars));
         }
-    }
 
-    struct Published {}
+        #[test]
+        fn it_applies_redactions_to_every_extractor() {
+            let mut post = Post::new().with_redactions(&["secret"]);
 
-    impl State for Published {
-        fn request_review(self: Box<Self>) -> Box<dyn State> {
-            self
-        }
-        fn approve(self: Box<Self>) -> Box<dyn State> {
-            self
-        }
-        fn content<'a>(&self, post: &'a Post) -> &'a str {
-            &post.content
+            post.add_text(
+                "Lunch was fine.\n\nThe secret plan. See [secret](https://example.com/secret).\n\n",
+            );
+            post.add_text("![secret](/secret.png) and a note[^1].\n\n[^1]: The secret.\n\n");
+            post.add_text("```secret\nsecret()\n```\n");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            let extracted = [
+                post.paragraphs().join(" "),
+                post.sentences().join(" "),
+                post.links().join(" "),
+                format!("{:?}", post.images()),
+                format!("{:?}", post.footnotes()),
+                format!("{:?}", post.code_blocks()),
+            ];
+            for text in extracted {
+                assert!(!text.contains("secret"), "{}", text);
+            }
+            assert_eq!(vec!["Lunch was fine."], post.paragraphs());
+            assert_eq!(vec!["Lunch was fine."], post.sentences());
+            assert!(post.links().is_empty());
         }
-        fn reject(self: Box<Self>) -> Box<dyn State> {
-            self
+
+        #[test]
+        fn it_extracts_from_the_plaintext_while_embargoed() {
+            let mut post = Post::new().with_embargo(9);
+
+            post.add_text("# Plan\n\nSee https://example.com/plan.\n\n[^1]: The plan.\n");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(vec!["https://example.com/plan"], post.links());
+            assert_eq!(vec![(1, String::from("Plan"))], post.headings());
+            assert_eq!(
+                vec![(String::from("1"), String::from("The plan."))],
+                post.footnotes()
+            );
+            assert_eq!("# Plan", post.paragraphs()[0]);
+            assert!(post.content_html().starts_with("<h1>Plan</h1>"));
         }
-    }
-}
 
-pub mod rust_way {
-    pub struct Post {
-        content: String,
-    }
+        #[test]
+        fn it_blocks_a_quorum_without_assigned_reviewers() {
+            let mut post = Post::new().with_review_quorum(1.0);
 
-    impl Post {
-        pub fn new() -> DraftPost {
-            DraftPost {
-                content: String::new(),
-            }
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(None, post.quorum_target());
+            post.approve();
+            post.approve();
+            assert_eq!(PostState::PendingReview, post.state());
+            assert_eq!(0.0, post.approval_progress());
+
+            post.assign_reviewer("alice", SystemTime::UNIX_EPOCH);
+            post.approve_by("alice");
+            assert_eq!(PostState::Published, post.state());
         }
 
-        pub fn content(&self) -> &str {
-            &self.content
+        #[test]
+        fn it_clamps_out_of_range_quorum_fractions() {
+            let assign = |fraction: f64| {
+                let mut post = Post::new().with_review_quorum(fraction);
+                for name in ["alice", "bob", "carol", "dave"] {
+                    post.assign_reviewer(name, SystemTime::UNIX_EPOCH);
+                }
+                post.quorum_target()
+            };
+
+            assert_eq!(Some(1), assign(-0.5));
+            assert_eq!(Some(1), assign(0.0));
+            assert_eq!(Some(4), assign(2.0));
+            assert_eq!(Some(4), assign(f64::NAN));
         }
-    }
-    pub struct DraftPost {
-        content: String,
-    }
 
-    impl DraftPost {
-        pub fn add_text(&mut self, text: &str) {
-            self.content.push_str(text);
+        #[test]
+        fn it_replays_cleared_text_and_named_reviews() {
+            let mut post = Post::new()
+                .with_reviewer_allowlist(&["alice", "bob"])
+                .with_required_roles(&["legal"])
+                .with_required_rejections(2);
+            post.start_recording();
+
+            post.add_text("a");
+            post.clear_text();
+            post.add_text("b");
+            post.request_review();
+            post.approve_by("mallory");
+            post.approve_with_comment("Tasty");
+            post.reject_by("bob");
+            post.approve_by_role("alice", "legal");
+
+            let mut replayed = Post::new()
+                .with_reviewer_allowlist(&["alice", "bob"])
+                .with_required_roles(&["legal"])
+                .with_required_rejections(2);
+            replayed.apply_all(post.recorded_commands().to_vec());
+
+            assert_eq!(1, replayed.byte_len());
+            assert_eq!(post.state(), replayed.state());
+            assert_eq!(PostState::Published, replayed.state());
+            assert_eq!(post.comments(), replayed.comments());
+            assert_eq!(
+                post.recorded_commands()[4],
+                Command::ApproveBy(String::from("mallory"))
+            );
+            assert_eq!(8, post.recorded_commands().len());
         }
 
-        pub fn request_review(self) -> PendingReviewPost {
-            PendingReviewPost {
-                content: self.content,
-            }
+        #[test]
+        fn it_round_trips_review_policy_timestamps_and_revisions() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut note = Post::new()
+                .with_clock(clock.clone())
+                .with_type(PostType::Announcement)
+                .with_required_roles(&["legal"]);
+            note.add_text("A quick note");
+            note.request_review();
+            note.approve_by_role("alice", "legal");
+            note.add_tag("news");
+            clock.advance(Duration::from_secs(30));
+            let mut published = Post::new().with_clock(clock.clone());
+            published.add_text("First draft");
+            clock.advance(Duration::from_secs(30));
+            published.request_review();
+            published.approve();
+            published.approve();
+            published.revise("Second draft");
+
+            let json = export_all(&[note, published]);
+            let posts = import_all(&json).unwrap();
+
+            assert_eq!(json, export_all(&posts));
+            assert_eq!(PostType::Announcement, posts[0].post_type());
+            assert_eq!(PostState::PendingReview, posts[0].state());
+            assert!(posts[0].outstanding_roles().is_empty());
+            assert_eq!([String::from("news")], posts[0].tags());
+            assert_eq!(2, posts[1].version());
+            assert_eq!(
+                SystemTime::UNIX_EPOCH + Duration::from_secs(60),
+                posts[1].to_feed_item().unwrap().published_at
+            );
+            assert_eq!(
+                vec![
+                    DiffOp::Delete(String::from("First draft")),
+                    DiffOp::Insert(String::from("Second draft"))
+                ],
+                posts[1].diff_with_previous()
+            );
         }
-    }
-    pub struct PendingReviewPost {
-        content: String,
-    }
 
-    impl PendingReviewPost {
-        pub fn approve(self) -> ApprovedPendingReviewPost {
-            ApprovedPendingReviewPost {
-                content: self.content,
-            }
+        #[test]
+        fn it_publishes_an_imported_pending_note_after_one_more_approval() {
+            let mut note = Post::new().with_type(PostType::Note);
+            note.add_text("A quick note");
+            note.request_review();
+
+            let mut posts = import_all(&export_all(&[note])).unwrap();
+            posts[0].approve();
+            assert_eq!(PostState::Published, posts[0].state());
         }
 
-        pub fn reject(self) -> DraftPost {
-            DraftPost {
-                content: self.content,
-            }
+        #[test]
+        fn it_refuses_deeply_nested_imports() {
+            assert_eq!(
+                Some(PostError::InvalidExport),
+                import_all(&"[".repeat(200000)).err()
+            );
         }
-    }
 
-    pub struct ApprovedPendingReviewPost {
-        content: String,
-    }
+        #[test]
+        fn it_forgets_a_vetoed_named_approval() {
+            let allowed = Rc::new(Cell::new(false));
+            let guard = allowed.clone();
+            let mut post = Post::new().with_publish_guard(move |_| guard.get());
 
-    impl ApprovedPendingReviewPost {
-        pub fn approve(self) -> Post {
-            Post {
-                content: self.content,
-            }
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.approve_by("alice");
+            post.approve_by("bob");
+            assert_eq!(1, post.publish_vetoes());
+            assert_eq!(1, post.current_approval_weight());
+            assert_eq!(0.5, post.approval_progress());
+
+            allowed.set(true);
+            post.approve_by("bob");
+            assert_eq!(PostState::Published, post.state());
         }
+        #[test]
+        fn it_estimates_publish_time_against_the_review_quorum() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new()
+                .with_clock(clock.clone())
+                .with_review_quorum(1.0);
+            let interval = Duration::from_secs(60);
 
-        pub fn reject(self) -> DraftPost {
-            DraftPost {
-                content: self.content,
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(None, post.estimated_publish_time(interval));
+
+            for name in ["alice", "bob", "carol"] {
+                post.assign_reviewer(name, SystemTime::UNIX_EPOCH);
             }
+            assert_eq!(
+                Some(SystemTime::UNIX_EPOCH + interval * 3),
+                post.estimated_publish_time(interval)
+            );
+
+            post.approve_by("alice");
+            assert_eq!(
+                Some(SystemTime::UNIX_EPOCH + interval * 2),
+                post.estimated_publish_time(interval)
+            );
         }
-    }
-}
+        #[test]
+        fn it_forks_a_pending_note_that_publishes_after_one_approval() {
+            let mut post = Post::new().with_type(PostType::Note);
 
-#[cfg(test)]
-mod tests {
-    mod oop {
-        use crate::oop::Post;
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+
+            let mut fork = post.fork();
+            fork.approve();
+            assert_eq!(PostState::Published, fork.state());
+            assert_eq!(PostState::PendingReview, post.state());
+        }
 
         #[test]
-        fn it_adds_text_only_in_draft() {
-            let mut post = Post::new();
+        fn it_keeps_the_review_policy_on_a_draft_clone() {
+            let mut post = Post::new()
+                .with_type(PostType::Note)
+                .with_required_roles(&["legal"]);
 
             post.add_text("I ate a salad for lunch today");
-            assert_eq!("", post.content());
+            let mut draft = post.clone_as_draft();
+            draft.request_review();
+            draft.approve();
+            assert_eq!(PostState::PendingReview, draft.state());
 
+            draft.approve_by_role("alice", "legal");
+            assert_eq!(PostState::Published, draft.state());
+        }
+        #[test]
+        fn it_keeps_redaction_and_embargo_on_a_fork() {
+            let mut post = Post::new().with_redactions(&["secret"]).with_embargo(3);
+
+            post.add_text("the secret plan");
             post.request_review();
-            assert_eq!("", post.content());
+            post.approve();
+            post.approve();
 
-            post.add_text("and a steak for dinner");
-            assert_eq!("", post.content());
+            let mut fork = post.fork();
+            assert_eq!(post.content(), fork.content());
 
-            post.approve();
-            assert_eq!("", post.content());
+            fork.lift_embargo();
+            assert_eq!("the ██████ plan", fork.content());
+        }
+        #[test]
+        fn it_previews_a_vetoed_publication_as_pending() {
+            let mut post = Post::new()
+                .with_type(PostType::Note)
+                .with_publish_guard(|_| false);
+
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            assert_eq!(
+                PostState::PendingReview,
+                post.preview_transition(Action::Approve)
+            );
 
             post.approve();
-            assert_eq!("I ate a salad for lunch today", post.content());
+            assert_eq!(PostState::PendingReview, post.state());
         }
-
         #[test]
-        fn it_can_reject_posts() {
-            let mut post = Post::new();
+        fn it_offers_no_actions_during_a_cooldown() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new()
+                .with_clock(clock.clone())
+                .with_transition_cooldown(Duration::from_secs(60));
 
             post.add_text("I ate a salad for lunch today");
-            assert_eq!("", post.content());
-
             post.request_review();
-            assert_eq!("", post.content());
+            assert!(post.available_actions().is_empty());
+            assert_eq!(
+                PostState::PendingReview,
+                post.preview_transition(Action::Reject)
+            );
 
             post.reject();
-            assert_eq!("", post.content());
+            assert_eq!(PostState::PendingReview, post.state());
 
-            post.add_text(" and a steak for dinner");
-            assert_eq!("", post.content());
+            clock.advance(Duration::from_secs(60));
+            assert!(post.available_actions().contains(&Action::Reject));
+            assert_eq!(PostState::Draft, post.preview_transition(Action::Reject));
+        }
+        #[test]
+        fn it_ignores_comments_in_fenced_code_when_normalizing_headings() {
+            let mut post = Post::new().with_normalized_headings(true);
 
+            post.add_text("### Setup\n```bash\n# install the TOOL\n```\n#### Run\n");
             post.request_review();
             post.approve();
             post.approve();
+
             assert_eq!(
-                "I ate a salad for lunch today and a steak for dinner",
+                "# Setup\n```bash\n# install the TOOL\n```\n## Run\n",
                 post.content()
             );
+            assert_eq!(
+                vec![(1, String::from("Setup")), (2, String::from("Run"))],
+                post.headings()
+            );
         }
+        #[test]
+        fn it_leaves_fenced_code_alone_when_title_casing_headings() {
+            let mut post = Post::new().with_title_case_headings(true);
 
+            post.add_text("# setup\n```bash\n# install the TOOL\n```\n");
+            post.request_review();
+            post.approve();
+            post.approve();
+
+            assert_eq!(
+                "# Setup\n```bash\n# install the TOOL\n```\n",
+                post.content()
+            );
+        }
         #[test]
-        fn it_requires_two_approvals() {
+        fn it_gives_no_estimate_when_the_publish_time_overflows() {
             let mut post = Post::new();
 
             post.add_text("I ate a salad for lunch today");
-            assert_eq!("", post.content());
-
             post.request_review();
-            assert_eq!("", post.content());
-
-            post.approve();
-            assert_eq!("", post.content());
+            assert_eq!(None, post.estimated_publish_time(Duration::MAX));
         }
+        #[test]
+        fn it_round_trips_the_review_policy_through_an_export() {
+            let deadline = SystemTime::UNIX_EPOCH + Duration::from_secs(60);
+            let mut post = Post::new()
+                .with_required_rejections(3)
+                .with_reviewer_allowlist(&["alice", "bob", "carol"])
+                .with_approval_weights(&[("alice", 2)])
+                .with_review_quorum(0.5);
+            post.add_text("I ate a salad for lunch today");
+            post.request_review();
+            post.assign_reviewer("alice", deadline);
+            post.assign_reviewer("bob", deadline);
+            post.assign_reviewer("carol", deadline);
+            post.reject();
+
+            let json = export_all(&[post]);
+            let mut posts = import_all(&json).unwrap();
+            assert_eq!(json, export_all(&posts));
+            assert_eq!(Some(2), posts[0].quorum_target());
 
+            posts[0].reject();
+            assert_eq!(PostState::PendingReview, posts[0].state());
+            posts[0].approve_by("dave");
+            posts[0].approve_by("alice");
+            assert_eq!(PostState::PendingReview, posts[0].state());
+            posts[0].approve_by("bob");
+            assert_eq!(PostState::Published, posts[0].state());
+        }
         #[test]
-        fn it_prints_content_when_published() {
+        fn it_replays_recorded_revisions() {
             let mut post = Post::new();
+            post.start_recording();
 
-            post.add_text("I ate a salad for lunch today");
-            assert_eq!("", post.content());
+            post.add_text("a");
+            post.request_review();
+            post.approve();
+            post.approve();
+            post.revise("b");
+            post.revise("c");
+            post.revert_to_revision(2);
+
+            let mut replayed = Post::new();
+            replayed.apply_all(post.recorded_commands().to_vec());
+            assert_eq!("b", replayed.content());
+            assert_eq!(4, replayed.version());
+            assert_eq!(
+                &[
+                    Command::Revise(String::from("b")),
+                    Command::Revise(String::from("c")),
+                    Command::RevertToRevision(2)
+                ],
+                &post.recorded_commands()[4..]
+            );
+        }
+        #[test]
+        fn it_drops_the_comment_of_an_approval_that_does_not_count() {
+            let clock = Rc::new(FixedClock::new(SystemTime::UNIX_EPOCH));
+            let mut post = Post::new()
+                .with_type(PostType::Note)
+                .with_clock(clock.clone())
+                .with_transition_cooldown(Duration::from_secs(60));
 
+            post.add_text("I ate a salad for lunch today");
             post.request_review();
-            assert_eq!("", post.content());
+            post.approve_with_comment("lgtm");
+            assert!(post.comments().is_empty());
 
-            post.approve();
-            assert_eq!("", post.content());
+            let mut vetoed = Post::new()
+                .with_type(PostType::Note)
+                .with_publish_guard(|_| false);
+            vetoed.add_text("I ate a salad for lunch today");
+            vetoed.request_review();
+            vetoed.approve_with_comment("lgtm");
+            assert!(vetoed.comments().is_empty());
 
-            post.approve();
-            assert_eq!("I ate a salad for lunch today", post.content());
+            clock.advance(Duration::from_secs(60));
+            post.approve_with_comment("lgtm");
+            assert_eq!(&[String::from("lgtm")], post.comments());
         }
     }
 
@@ -297,5 +6378,119 @@ mod tests {
                 post.content()
             );
         }
+
+        #[test]
+        fn it_keeps_an_empty_draft_when_review_is_refused() {
+            let post = Post::new();
+
+            let mut post = match post.try_request_review() {
+                Ok(_) => panic!("an empty draft should not be submitted"),
+                Err(draft) => draft,
+            };
+
+            post.add_text("I ate a salad for lunch today");
+
+            let post = match post.try_request_review() {
+                Ok(pending) => pending,
+                Err(_) => panic!("a written draft should be submitted"),
+            };
+
+            let post = post.approve().approve();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_can_reject_and_clear_a_blog() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+
+            let post = post.request_review();
+
+            let mut post = post.reject_and_clear();
+
+            post.add_text("I had a steak for dinner");
+
+            let post = post.request_review().approve().approve();
+            assert_eq!("I had a steak for dinner", post.content());
+        }
+
+        #[test]
+        fn it_can_archive_a_blog() {
+            let mut post = Post::new();
+
+            post.add_text("I ate a salad for lunch today");
+
+            let post = post.request_review().approve().approve();
+
+            let post = post.archive();
+            assert_eq!("I ate a salad for lunch today", post.content());
+        }
+
+        #[test]
+        fn it_can_read_back_a_draft() {
+            let mut post = Post::new();
+            assert_eq!("", post.content());
+
+            post.add_text("I ate a salad for lunch today");
+            post.add_text(" and it was delicious!");
+            assert_eq!(
+                "I ate a salad for lunch today and it was delicious!",
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_reports_the_approvals_still_needed() {
+            assert_eq!(2, PendingReviewPost::<String>::approvals_needed());
+            assert_eq!(1, ApprovedPendingReviewPost::<String>::approvals_needed());
+        }
+
+        #[test]
+        fn it_reports_the_approvals_still_needed_for_list_content() {
+            assert_eq!(2, PendingReviewPost::<Vec<String>>::approvals_needed());
+            assert_eq!(
+                1,
+                ApprovedPendingReviewPost::<Vec<String>>::approvals_needed()
+            );
+        }
+
+        #[test]
+        fn it_can_publish_a_blog_with_list_content() {
+            let mut post: DraftPost<Vec<String>> = DraftPost::new();
+
+            post.add_text("I ate a salad for lunch today");
+            post.add_text("and it was delicious!");
+            assert_eq!(2, post.content().len());
+
+            let post = post.request_review().approve().approve();
+            assert_eq!(
+                ["I ate a salad for lunch today", "and it was delicious!"],
+                post.content()
+            );
+        }
+
+        #[test]
+        fn it_keeps_empty_list_content_out_of_review() {
+            let post: DraftPost<Vec<String>> = DraftPost::new();
+
+            let mut post = match post.try_request_review() {
+                Ok(_) => panic!("an empty draft should not be submitted"),
+                Err(draft) => draft,
+            };
+
+            post.add_text("I ate a salad for lunch today");
+            let post = match post.try_request_review() {
+                Ok(pending) => pending,
+                Err(_) => panic!("a written draft should be submitted"),
+            };
+
+            let mut post = post.reject_and_clear();
+            assert!(post.content().is_empty());
+
+            post.add_text("I had a steak for dinner");
+            let post = post.request_review().approve().approve();
+            assert_eq!(["I had a steak for dinner"], post.content());
+        }
     }
 }